@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
-use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{TokenEconomics, StakingPool, UserStake};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::{
+    TokenEconomics, StakingPool, UserStake, EmissionSchedule, RoleAssignment, PendingChange, UnbondingTicket,
+    AutoCompound, GovernanceConfig, Proposal, ProposalAction, VoteRecord, StakeCheckpoints, TreasuryConfig,
+    FeeDistribution, Referral, ReferrerAccount,
+};
 use crate::errors::EconomicsError;
+use crate::program::TokenEconomics as TokenEconomicsProgram;
 
 #[derive(Accounts)]
 pub struct InitializeEconomics<'info> {
@@ -19,41 +23,55 @@ pub struct InitializeEconomics<'info> {
     pub authority: Signer<'info>,
     
     /// Token used for staking
-    pub stake_mint: Account<'info, Mint>,
+    #[account(mint::token_program = stake_token_program)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
     
     /// Token used for rewards
-    pub reward_mint: Account<'info, Mint>,
+    #[account(mint::token_program = reward_token_program)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
     
-    /// Treasury vault for collecting fees
+    /// Treasury vault for collecting fees. The vaults are PDAs rather than
+    /// associated token accounts so they stay distinct when both mints are equal.
     #[account(
         init,
         payer = authority,
-        associated_token::mint = reward_mint,
-        associated_token::authority = economics
+        seeds = [b"treasury_vault"],
+        bump,
+        token::mint = reward_mint,
+        token::authority = economics,
+        token::token_program = reward_token_program
     )]
-    pub treasury_vault: Account<'info, TokenAccount>,
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Vault for storing staked tokens
     #[account(
         init,
         payer = authority,
-        associated_token::mint = stake_mint,
-        associated_token::authority = economics
+        seeds = [b"stake_vault"],
+        bump,
+        token::mint = stake_mint,
+        token::authority = economics,
+        token::token_program = stake_token_program
     )]
-    pub stake_vault: Account<'info, TokenAccount>,
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Vault for reward token distribution
     #[account(
         init,
         payer = authority,
-        associated_token::mint = reward_mint,
-        associated_token::authority = economics
+        seeds = [b"reward_vault"],
+        bump,
+        token::mint = reward_mint,
+        token::authority = economics,
+        token::token_program = reward_token_program
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
     
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// Token program owning the stake mint (SPL Token or Token-2022)
+    pub stake_token_program: Interface<'info, TokenInterface>,
+    /// Token program owning the reward mint (SPL Token or Token-2022)
+    pub reward_token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
@@ -62,11 +80,13 @@ pub struct CreateStakingPool<'info> {
     #[account(
         mut,
         seeds = [b"economics"],
-        bump = economics.bump,
-        has_one = authority
+        bump = economics.bump
     )]
     pub economics: Account<'info, TokenEconomics>,
     
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
     #[account(
         init,
         payer = authority,
@@ -76,12 +96,68 @@ pub struct CreateStakingPool<'info> {
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
+    /// Economics authority or a pool manager
     #[account(mut)]
     pub authority: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct UpdateStakingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a pool manager
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct CloseStakingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// `close` zeroes the data and hands the account back to the system
+    /// program, so it cannot be revived within the same transaction
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        close = authority
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
 // Fixed: Proper bump handling for init_if_needed
 #[derive(Accounts)]
 #[instruction(pool_id: u8)]
@@ -101,8 +177,11 @@ pub struct StakeTokens<'info> {
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
     #[account(
-        init,
+        init_if_needed,
         payer = user,
         space = UserStake::LEN,
         seeds = [b"user_stake", user.key().as_ref(), pool_id.to_le_bytes().as_ref()],
@@ -110,6 +189,38 @@ pub struct StakeTokens<'info> {
     )]
     pub user_stake: Account<'info, UserStake>,
     
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    /// Referrer to bind the staker to, when staking through a referral
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+    
+    /// Staker's referral binding, required with `referrer_account`
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Referral::LEN,
+        seeds = [b"referral", user.key().as_ref()],
+        bump
+    )]
+    pub referral: Option<Account<'info, Referral>>,
+    
+    /// CHECK: Token account holding a collection NFT to earn the pool's NFT
+    /// boost, or the recorded one to show it is no longer held; checked in the handler
+    pub nft_token_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Metaplex metadata of the held NFT; owner, address and collection
+    /// are checked in the handler
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
@@ -117,19 +228,19 @@ pub struct StakeTokens<'info> {
     #[account(
         mut,
         associated_token::mint = economics.stake_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     
     /// Vault where staked tokens are stored
-    #[account(
-        mut,
-        associated_token::mint = economics.stake_mint,
-        associated_token::authority = economics
-    )]
-    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
@@ -149,6 +260,21 @@ pub struct ClaimRewards<'info> {
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Active fee split, required while `economics.fee_distribution` is set
+    pub fee_distribution: Option<Account<'info, FeeDistribution>>,
+    
+    /// CHECK: Staker's referral binding; may not exist. Seeds are checked here,
+    /// owner and discriminator in the handler when it does.
+    #[account(seeds = [b"referral", user.key().as_ref()], bump)]
+    pub referral: UncheckedAccount<'info>,
+    
+    /// Referrer of a referred staker, credited with part of the governance fee
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+    
     #[account(
         mut,
         seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
@@ -164,27 +290,192 @@ pub struct ClaimRewards<'info> {
     #[account(
         mut,
         associated_token::mint = economics.reward_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_reward_account: Account<'info, TokenAccount>,
+    pub user_reward_account: InterfaceAccount<'info, TokenAccount>,
     
     /// Treasury vault for governance fees
+    #[account(mut, address = economics.treasury_vault)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Reward vault for token distribution
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Writable so a fee share can be burned
+    #[account(mut, address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAll<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Active fee split, required while `economics.fee_distribution` is set
+    pub fee_distribution: Option<Account<'info, FeeDistribution>>,
+    
+    /// CHECK: Staker's referral binding; may not exist. Seeds are checked here,
+    /// owner and discriminator in the handler when it does.
+    #[account(seeds = [b"referral", user.key().as_ref()], bump)]
+    pub referral: UncheckedAccount<'info>,
+    
+    /// Referrer of a referred staker, credited with part of the governance fee
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// User's reward token account
     #[account(
         mut,
         associated_token::mint = economics.reward_mint,
-        associated_token::authority = economics
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub treasury_vault: Account<'info, TokenAccount>,
+    pub user_reward_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Treasury vault for governance fees
+    #[account(mut, address = economics.treasury_vault)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
     
     /// Reward vault for token distribution
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Writable so a fee share can be burned
+    #[account(mut, address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Compound<'info> {
     #[account(
         mut,
-        associated_token::mint = economics.reward_mint,
-        associated_token::authority = economics
+        seeds = [b"economics"],
+        bump = economics.bump,
+        constraint = economics.stake_mint == economics.reward_mint @ EconomicsError::CompoundUnavailable
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        constraint = staking_pool.is_active @ EconomicsError::PoolNotActive
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Active fee split, required while `economics.fee_distribution` is set
+    pub fee_distribution: Option<Account<'info, FeeDistribution>>,
+    
+    /// CHECK: Staker's referral binding; may not exist. Seeds are checked here,
+    /// owner and discriminator in the handler when it does.
+    #[account(seeds = [b"referral", user_stake.user.as_ref()], bump)]
+    pub referral: UncheckedAccount<'info>,
+    
+    /// Referrer of a referred staker, credited with part of the governance fee
+    #[account(mut)]
+    pub referrer_account: Option<Account<'info, ReferrerAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stake", user_stake.user.as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history backing the position's voting power; required when the
+    /// owner compounds, ignored for cranks
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Option<Account<'info, StakeCheckpoints>>,
+    
+    /// Opt-in marker, required when the caller is not the position owner
+    pub auto_compound: Option<Account<'info, AutoCompound>>,
+    
+    /// Position owner, or any crank for opted-in positions
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    /// Treasury vault for governance fees
+    #[account(mut, address = economics.treasury_vault)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Reward vault the compounded rewards are taken from
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Vault where staked tokens are stored
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Writable so a fee share can be burned
+    #[account(mut, address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableAutoCompound<'info> {
+    #[account(
+        seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = user
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = AutoCompound::LEN,
+        seeds = [b"auto_compound", user_stake.key().as_ref()],
+        bump
+    )]
+    pub auto_compound: Account<'info, AutoCompound>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DisableAutoCompound<'info> {
+    #[account(
+        mut,
+        seeds = [b"auto_compound", auto_compound.user_stake.as_ref()],
+        bump = auto_compound.bump,
+        has_one = user,
+        close = user
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub auto_compound: Account<'info, AutoCompound>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(mut)]
+    pub user: Signer<'info>,
 }
 
 #[derive(Accounts)]
@@ -203,6 +494,9 @@ pub struct UnstakeTokens<'info> {
     )]
     pub staking_pool: Account<'info, StakingPool>,
     
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
     #[account(
         mut,
         seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
@@ -211,6 +505,16 @@ pub struct UnstakeTokens<'info> {
     )]
     pub user_stake: Account<'info, UserStake>,
     
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
@@ -218,23 +522,24 @@ pub struct UnstakeTokens<'info> {
     #[account(
         mut,
         associated_token::mint = economics.stake_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     
     /// Vault where staked tokens are stored
-    #[account(
-        mut,
-        associated_token::mint = economics.stake_mint,
-        associated_token::authority = economics
-    )]
-    pub stake_vault: Account<'info, TokenAccount>,
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
     
-    pub token_program: Program<'info, Token>,
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct EmergencyUnstake<'info> {
+pub struct UnstakeEarly<'info> {
     #[account(
         mut,
         seeds = [b"economics"],
@@ -242,6 +547,16 @@ pub struct EmergencyUnstake<'info> {
     )]
     pub economics: Account<'info, TokenEconomics>,
     
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
     #[account(
         mut,
         seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
@@ -250,65 +565,1309 @@ pub struct EmergencyUnstake<'info> {
     )]
     pub user_stake: Account<'info, UserStake>,
     
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
     
+    /// User's token account for receiving unstaked tokens
     #[account(
         mut,
         associated_token::mint = economics.stake_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Vault where staked tokens are stored
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Receives penalties redistributed to stakers
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
     
+    /// Stake-token vault collecting penalties for the treasury
     #[account(
-        mut,
-        associated_token::mint = economics.stake_mint,
-        associated_token::authority = economics
+        init_if_needed,
+        payer = user,
+        seeds = [b"penalty_vault"],
+        bump,
+        token::mint = stake_mint,
+        token::authority = economics,
+        token::token_program = token_program
     )]
-    pub stake_vault: Account<'info, TokenAccount>,
+    pub penalty_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct AdminControl<'info> {
+pub struct RequestUnstake<'info> {
     #[account(
         mut,
         seeds = [b"economics"],
-        bump = economics.bump,
-        has_one = authority
+        bump = economics.bump
     )]
     pub economics: Account<'info, TokenEconomics>,
     
-    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = user
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = UnbondingTicket::LEN,
+        seeds = [
+            b"unbonding",
+            staking_pool.pool_id.to_le_bytes().as_ref(),
+            staking_pool.next_ticket_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct FundRewards<'info> {
+pub struct WithdrawUnbonded<'info> {
     #[account(
-        mut,
         seeds = [b"economics"],
-        bump = economics.bump,
-        has_one = authority
+        bump = economics.bump
     )]
     pub economics: Account<'info, TokenEconomics>,
     
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
     #[account(
         mut,
-        associated_token::mint = economics.reward_mint,
-        associated_token::authority = authority
+        seeds = [b"pool", unbonding_ticket.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
     )]
-    pub authority_token_account: Account<'info, TokenAccount>,
+    pub staking_pool: Account<'info, StakingPool>,
     
     #[account(
         mut,
-        associated_token::mint = economics.reward_mint,
-        associated_token::authority = economics
+        seeds = [
+            b"unbonding",
+            unbonding_ticket.pool_id.to_le_bytes().as_ref(),
+            unbonding_ticket.ticket_id.to_le_bytes().as_ref()
+        ],
+        bump = unbonding_ticket.bump,
+        has_one = user,
+        close = user
     )]
-    pub reward_vault: Account<'info, TokenAccount>,
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
     
-    pub token_program: Program<'info, Token>,
-}
\ No newline at end of file
+    /// User's token account for receiving unbonded tokens
+    #[account(
+        mut,
+        associated_token::mint = economics.stake_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Vault where staked tokens are stored
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelUnbonding<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", unbonding_ticket.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump,
+        constraint = staking_pool.is_active @ EconomicsError::PoolNotActive
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref(), unbonding_ticket.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = user
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"unbonding",
+            unbonding_ticket.pool_id.to_le_bytes().as_ref(),
+            unbonding_ticket.ticket_id.to_le_bytes().as_ref()
+        ],
+        bump = unbonding_ticket.bump,
+        has_one = user,
+        close = user
+    )]
+    pub unbonding_ticket: Account<'info, UnbondingTicket>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserStake<'info> {
+    /// `close` zeroes the data and hands the account back to the system
+    /// program; staking again starts a fresh position via `init_if_needed`
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = user,
+        close = user
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Stake history of the position, closed with it; omit only for positions
+    /// that never recorded one
+    #[account(
+        mut,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump = checkpoints.bump,
+        close = user
+    )]
+    pub checkpoints: Option<Account<'info, StakeCheckpoints>>,
+    
+    /// Auto-compound opt-in of the position, closed with it so a later
+    /// position at the same address does not inherit it; omit only when
+    /// auto-compounding is off
+    #[account(
+        mut,
+        seeds = [b"auto_compound", user_stake.key().as_ref()],
+        bump = auto_compound.bump,
+        has_one = user,
+        close = user
+    )]
+    pub auto_compound: Option<Account<'info, AutoCompound>>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshBoost<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stake", user_stake.user.as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// CHECK: Token account holding a collection NFT to earn the pool's NFT
+    /// boost, or the recorded one to show it is no longer held; checked in the handler
+    pub nft_token_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Metaplex metadata of the held NFT; owner, address and collection
+    /// are checked in the handler
+    pub nft_metadata: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyUnstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        has_one = user
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history backing the position's voting power
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = economics.stake_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, address = economics.stake_vault)]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AdminControl<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    pub authority: Signer<'info>,
+}
+
+/// Admin instructions that can also be performed by a delegated role holder
+#[derive(Accounts)]
+pub struct RoleControl<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a member holding the instruction's role
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRewardRate<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a member holding the rate manager role
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct GrantRole<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RoleAssignment::LEN,
+        seeds = [b"role", member.as_ref()],
+        bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(member: Pubkey)]
+pub struct RevokeRole<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"role", member.as_ref()],
+        bump = role_assignment.bump
+    )]
+    pub role_assignment: Account<'info, RoleAssignment>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct QueueParameterChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = PendingChange::LEN,
+        seeds = [b"pending_change", economics.next_change_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a member holding the change's role
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(change_id: u64)]
+pub struct ExecuteParameterChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pending_change", change_id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    
+    /// Target pool, required for pool parameter changes
+    #[account(mut)]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set.
+    /// Emission changes rewrite it, creating it on first use.
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = EmissionSchedule::LEN,
+        seeds = [b"emission_schedule"],
+        bump
+    )]
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// CHECK: receives the rent of the pending change, validated by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    
+    /// Pays for the emission schedule when an emission change creates it
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
+    
+    pub system_program: Option<Program<'info, System>>,
+}
+
+#[derive(Accounts)]
+#[instruction(change_id: u64)]
+pub struct CancelParameterChange<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pending_change", change_id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+    
+    /// CHECK: receives the rent of the pending change, validated by `has_one`
+    #[account(mut)]
+    pub proposer: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        constraint = economics.pending_authority != Pubkey::default() @ EconomicsError::NoPendingAuthority,
+        constraint = economics.pending_authority == new_authority.key() @ EconomicsError::InvalidPendingAuthority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a treasurer
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = economics.reward_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct NotifyRewardAmount<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a treasurer
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = economics.reward_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = referrer,
+        space = ReferrerAccount::LEN,
+        seeds = [b"referrer", referrer.key().as_ref()],
+        bump
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+    
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"referrer", referrer.key().as_ref()],
+        bump = referrer_account.bump,
+        has_one = referrer
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+    
+    pub referrer: Signer<'info>,
+    
+    /// Referrer's reward token account
+    #[account(
+        mut,
+        associated_token::mint = economics.reward_mint,
+        associated_token::authority = referrer,
+        associated_token::token_program = token_program
+    )]
+    pub referrer_reward_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut, address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FeeDistribution::LEN,
+        seeds = [b"fee_distribution"],
+        bump
+    )]
+    pub fee_distribution: Account<'info, FeeDistribution>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTreasury<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TreasuryConfig::LEN,
+        seeds = [b"treasury_config"],
+        bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury_config"],
+        bump = treasury_config.bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a treasurer
+    pub authority: Signer<'info>,
+    
+    #[account(mut, address = economics.treasury_vault)]
+    pub treasury_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Allowlisted token account receiving the withdrawal
+    #[account(mut)]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.reward_mint)]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawPenalties<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury_config"],
+        bump = treasury_config.bump
+    )]
+    pub treasury_config: Account<'info, TreasuryConfig>,
+    
+    /// Role assignment of the signer; omit when signing as the economics authority
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// Economics authority or a treasurer
+    pub authority: Signer<'info>,
+    
+    /// Stake-token vault collecting early-exit penalties for the treasury
+    #[account(
+        mut,
+        seeds = [b"penalty_vault"],
+        bump
+    )]
+    pub penalty_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    /// Allowlisted token account receiving the withdrawal
+    #[account(mut)]
+    pub recipient: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = economics.stake_mint)]
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SyncRewardFunding<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    #[account(address = economics.reward_vault)]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct MigrateStakingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// CHECK: May still use a legacy layout that `Account` cannot deserialize.
+    /// Seeds and owner are checked here, the discriminator in the handler.
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub staking_pool: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct MigrateUserStake<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// CHECK: May still use a legacy layout that `Account` cannot deserialize.
+    /// Seeds and owner are checked here, the discriminator in the handler.
+    #[account(
+        mut,
+        seeds = [b"user_stake", user.key().as_ref(), pool_id.to_le_bytes().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub user_stake: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetEmissionSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = EmissionSchedule::LEN,
+        seeds = [b"emission_schedule"],
+        bump
+    )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClearEmissionSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateEconomics<'info> {
+    /// CHECK: May still use a legacy layout that `Account` cannot deserialize.
+    /// Seeds and owner are checked here, the discriminator in the handler.
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump,
+        owner = crate::ID
+    )]
+    pub economics: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct AddRewardStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Co-incentive reward token; must differ from the stake and primary reward mints
+    #[account(
+        mint::token_program = token_program,
+        constraint = stream_mint.key() != economics.stake_mint @ EconomicsError::InvalidRewardStream,
+        constraint = stream_mint.key() != economics.reward_mint @ EconomicsError::InvalidRewardStream
+    )]
+    pub stream_mint: InterfaceAccount<'info, Mint>,
+    
+    /// Vault dedicated to this pool's stream, so partners fund pools in isolation
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"stream_vault", staking_pool.key().as_ref(), stream_mint.key().as_ref()],
+        bump,
+        token::mint = stream_mint,
+        token::authority = economics,
+        token::token_program = token_program
+    )]
+    pub stream_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct FundRewardStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    #[account(mut)]
+    pub stream_vault: InterfaceAccount<'info, TokenAccount>,
+    
+    pub funder: Signer<'info>,
+    
+    #[account(
+        mut,
+        token::mint = stream_vault.mint,
+        token::authority = funder,
+        token::token_program = token_program
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    
+    #[account(address = stream_vault.mint)]
+    pub stream_mint: InterfaceAccount<'info, Mint>,
+    
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(pool_id: u8)]
+pub struct UpdateRewardStream<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool_id.to_le_bytes().as_ref()],
+        bump = staking_pool.bump
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+    
+    /// Active emission schedule, required while `economics.emission_schedule` is set
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
+    /// Stream authority or economics authority
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump,
+        has_one = authority
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = GovernanceConfig::LEN,
+        seeds = [b"governance"],
+        bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    /// Data-less signer that executes passed proposals
+    #[account(
+        seeds = [b"governance_authority"],
+        bump
+    )]
+    pub governance_authority: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(action: ProposalAction)]
+pub struct CreateProposal<'info> {
+    #[account(
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [b"proposal", governance.next_proposal_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Proposer's position backing the proposal threshold
+    #[account(
+        seeds = [b"user_stake", proposer.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.user == proposer.key() @ EconomicsError::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history of the position
+    #[account(
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CastVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [b"user_stake", voter.key().as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump,
+        constraint = user_stake.user == voter.key() @ EconomicsError::Unauthorized
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    /// Stake history of the position
+    #[account(
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), user_stake.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CancelProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        has_one = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"economics"],
+        bump = economics.bump
+    )]
+    pub economics: Account<'info, TokenEconomics>,
+    
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, GovernanceConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance_authority"],
+        bump = governance.authority_bump
+    )]
+    pub governance_authority: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// Role assignment of the governance signer, when the action needs a role
+    pub role: Option<Account<'info, RoleAssignment>>,
+    
+    /// CHECK: Pending change created by the `queue_parameter_change` CPI, which
+    /// checks its seeds; required for `Parameter` actions
+    #[account(mut)]
+    pub pending_change: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Treasury config updated by the `withdraw_treasury` CPI, which
+    /// checks its seeds; required for `TreasurySpend` actions
+    #[account(mut)]
+    pub treasury_config: Option<UncheckedAccount<'info>>,
+    
+    /// Treasury vault, required for `TreasurySpend` actions
+    #[account(mut, address = economics.treasury_vault)]
+    pub treasury_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Token account named by a `TreasurySpend` action
+    #[account(mut)]
+    pub recipient: Option<InterfaceAccount<'info, TokenAccount>>,
+    
+    #[account(address = economics.reward_mint)]
+    pub reward_mint: Option<InterfaceAccount<'info, Mint>>,
+    
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    
+    /// Pays the pending change's rent for `Parameter` actions
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    
+    pub economics_program: Program<'info, TokenEconomicsProgram>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CloseVoteRecord<'info> {
+    #[account(
+        seeds = [b"proposal", proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), vote_record.user_stake.as_ref()],
+        bump = vote_record.bump,
+        has_one = voter,
+        close = voter
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CheckpointStake<'info> {
+    #[account(
+        seeds = [b"user_stake", user_stake.user.as_ref(), user_stake.pool_id.to_le_bytes().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = StakeCheckpoints::LEN,
+        seeds = [b"checkpoints", user_stake.key().as_ref()],
+        bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VotingPowerAt<'info> {
+    #[account(
+        seeds = [b"checkpoints", checkpoints.user_stake.as_ref()],
+        bump = checkpoints.bump
+    )]
+    pub checkpoints: Account<'info, StakeCheckpoints>,
+}
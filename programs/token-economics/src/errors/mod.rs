@@ -52,4 +52,172 @@ pub enum EconomicsError {
     
     #[msg("Unauthorized access")]
     Unauthorized,
+    
+    #[msg("Too many open stake lots - unstake matured lots first")]
+    TooManyStakeLots,
+    
+    #[msg("Lock duration must be 0 or between 1 week and 4 years")]
+    InvalidLockDuration,
+    
+    #[msg("Emission schedule account does not match the active schedule")]
+    EmissionScheduleMismatch,
+    
+    #[msg("Invalid emission schedule parameters")]
+    InvalidEmissionSchedule,
+    
+    #[msg("Pool already has the maximum number of reward streams")]
+    TooManyRewardStreams,
+    
+    #[msg("Reward stream not found or not usable for this pool")]
+    InvalidRewardStream,
+    
+    #[msg("Reward stream accounts missing or mismatched")]
+    InvalidRewardStreamAccounts,
+    
+    #[msg("Mint uses an unsupported Token-2022 extension")]
+    UnsupportedMintExtension,
+    
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    
+    #[msg("Signer does not hold the required role")]
+    MissingRole,
+    
+    #[msg("Parameters can only be changed through the timelock queue")]
+    TimelockActive,
+    
+    #[msg("Timelock delay has not elapsed")]
+    TimelockNotExpired,
+    
+    #[msg("Invalid timelock delay")]
+    InvalidTimelockDelay,
+    
+    #[msg("Staking pool does not match the parameter change")]
+    InvalidParameterChangePool,
+    
+    #[msg("Pool is already active")]
+    PoolAlreadyActive,
+    
+    #[msg("Position still holds stake or unclaimed rewards")]
+    PositionNotEmpty,
+    
+    #[msg("Pool still has stakers")]
+    PoolNotEmpty,
+    
+    #[msg("Pool must be deactivated first")]
+    PoolStillActive,
+    
+    #[msg("Pool has an unbonding period; use request_unstake")]
+    UnbondingRequired,
+    
+    #[msg("Unbonding period has not elapsed")]
+    UnbondingNotComplete,
+    
+    #[msg("Pool does not allow early exit")]
+    EarlyExitDisabled,
+    
+    #[msg("Invalid early-exit penalty configuration")]
+    InvalidPenaltyConfig,
+    
+    #[msg("Compounding requires the stake and reward mints to match")]
+    CompoundUnavailable,
+    
+    #[msg("Position has not opted into auto-compounding")]
+    AutoCompoundDisabled,
+    
+    #[msg("Claim-all position accounts missing, mismatched or duplicated")]
+    InvalidClaimAllAccounts,
+    
+    #[msg("Campaign duration out of range or reward too small for it")]
+    InvalidRewardCampaign,
+    
+    #[msg("Invalid governance configuration")]
+    InvalidGovernanceConfig,
+    
+    #[msg("Position has too little voting power")]
+    InsufficientVotingPower,
+    
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    
+    #[msg("Voting on this proposal is still open")]
+    VotingStillOpen,
+    
+    #[msg("Proposal did not reach quorum or a majority")]
+    ProposalNotPassed,
+    
+    #[msg("Proposal was already executed or cancelled")]
+    ProposalFinalized,
+    
+    #[msg("Accounts required by the proposal action are missing or mismatched")]
+    InvalidProposalAccounts,
+    
+    #[msg("Stake history does not reach back that far")]
+    CheckpointHistoryUnavailable,
+    
+    #[msg("Invalid treasury configuration")]
+    InvalidTreasuryConfig,
+    
+    #[msg("Recipient is not on the treasury allowlist")]
+    RecipientNotAllowed,
+    
+    #[msg("Withdrawal exceeds the treasury's epoch spending cap")]
+    TreasuryCapExceeded,
+    
+    #[msg("Invalid fee distribution")]
+    InvalidFeeDistribution,
+    
+    #[msg("Fee distribution account does not match the active one")]
+    FeeDistributionMismatch,
+    
+    #[msg("Fee recipient accounts do not match the fee distribution")]
+    InvalidFeeRecipientAccounts,
+    
+    #[msg("Cannot refer yourself")]
+    SelfReferral,
+    
+    #[msg("Staker is already bound to a different referrer")]
+    ReferrerAlreadySet,
+    
+    #[msg("Referral accounts do not match the staker's referrer")]
+    InvalidReferralAccounts,
+    
+    #[msg("Invalid NFT boost configuration")]
+    InvalidNftBoostConfig,
+    
+    #[msg("Pool does not offer an NFT boost")]
+    NftBoostDisabled,
+    
+    #[msg("NFT accounts do not prove a holding from the pool's collection")]
+    InvalidNftBoostAccounts,
+    
+    #[msg("Invalid loyalty tiers")]
+    InvalidLoyaltyTiers,
+    
+    #[msg("Pool accounts passed for settlement are invalid")]
+    InvalidSettlementAccounts,
+    
+    #[msg("Emission changes require the emission schedule account")]
+    EmissionScheduleRequired,
+    
+    #[msg("Pool still has user stake accounts that may be owed rewards")]
+    PoolHasOpenPositions,
+    
+    #[msg("Proposal was not executed within the execution grace period")]
+    ProposalExpired,
+    
+    #[msg("The position owner must pass its stake history")]
+    CheckpointsRequired,
+    
+    #[msg("Position already has a stake history")]
+    CheckpointHistoryExists,
+    
+    #[msg("Every staking pool must be passed exactly once for settlement")]
+    IncompleteSettlement,
+    
+    #[msg("Withdrawal amount must be greater than zero")]
+    InvalidWithdrawalAmount,
 }
\ No newline at end of file
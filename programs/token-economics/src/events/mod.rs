@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::{EmissionKind, Role, ParameterChange, PenaltyMode, PenaltyDestination, ProposalAction, FeeRecipient, LoyaltyTier};
 
 #[event]
 pub struct PoolCreatedEvent {
@@ -8,6 +9,44 @@ pub struct PoolCreatedEvent {
     pub max_capacity: u64,
 }
 
+#[event]
+pub struct StakingPoolUpdatedEvent {
+    pub pool_id: u8,
+    pub reward_multiplier: u16,
+    pub min_stake_period: i64,
+    pub max_capacity: u64,
+    pub unbonding_period: i64,
+}
+
+#[event]
+pub struct EarlyExitPenaltyUpdatedEvent {
+    pub pool_id: u8,
+    pub penalty_mode: PenaltyMode,
+    pub penalty_bps: u16,
+    pub penalty_destination: PenaltyDestination,
+}
+
+#[event]
+pub struct StakingPoolDeactivatedEvent {
+    pub pool_id: u8,
+}
+
+#[event]
+pub struct StakingPoolReactivatedEvent {
+    pub pool_id: u8,
+}
+
+#[event]
+pub struct StakingPoolClosedEvent {
+    pub pool_id: u8,
+}
+
+#[event]
+pub struct UserStakeClosedEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+}
+
 #[event]
 pub struct StakeEvent {
     pub user: Pubkey,
@@ -15,6 +54,16 @@ pub struct StakeEvent {
     pub amount: u64,
     pub total_user_stake: u64,
     pub total_pool_stake: u64,
+    pub lot_id: u32,
+    pub unlock_time: i64,
+    pub boosted_balance: u64,
+}
+
+/// Portion of a deposit lot released by an unstake
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ConsumedLot {
+    pub lot_id: u32,
+    pub amount: u64,
 }
 
 #[event]
@@ -23,6 +72,51 @@ pub struct UnstakeEvent {
     pub pool_id: u8,
     pub amount: u64,
     pub remaining_stake: u64,
+    pub consumed_lots: Vec<ConsumedLot>,
+}
+
+#[event]
+pub struct UnbondingRequestedEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub ticket_id: u64,
+    pub amount: u64,
+    pub withdrawable_at: i64,
+    pub remaining_stake: u64,
+    pub consumed_lots: Vec<ConsumedLot>,
+}
+
+#[event]
+pub struct UnbondedWithdrawnEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub ticket_id: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnbondingCancelledEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub ticket_id: u64,
+    pub amount: u64,
+    pub lot_id: u32,
+    pub total_user_stake: u64,
+}
+
+#[event]
+pub struct EarlyExitPenaltyEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub penalty: u64,
+    pub penalty_destination: PenaltyDestination,
+}
+
+/// Amount of one additional reward stream paid out by a claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct StreamRewardPaid {
+    pub mint: Pubkey,
+    pub amount: u64,
 }
 
 #[event]
@@ -32,6 +126,42 @@ pub struct RewardClaimEvent {
     pub gross_reward: u64,
     pub governance_fee: u64,
     pub net_reward: u64,
+    pub stream_rewards: Vec<StreamRewardPaid>,
+}
+
+/// Primary reward settled for one position by a batch claim
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PoolRewardClaimed {
+    pub pool_id: u8,
+    pub gross_reward: u64,
+}
+
+#[event]
+pub struct ClaimAllEvent {
+    pub user: Pubkey,
+    pub gross_reward: u64,
+    pub governance_fee: u64,
+    pub net_reward: u64,
+    pub pool_rewards: Vec<PoolRewardClaimed>,
+}
+
+#[event]
+pub struct CompoundEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub caller: Pubkey,
+    pub gross_reward: u64,
+    pub governance_fee: u64,
+    pub compounded: u64,
+    pub lot_id: u32,
+    pub total_user_stake: u64,
+}
+
+#[event]
+pub struct AutoCompoundChangedEvent {
+    pub user: Pubkey,
+    pub user_stake: Pubkey,
+    pub enabled: bool,
 }
 
 #[event]
@@ -58,4 +188,276 @@ pub struct RewardRateUpdatedEvent {
 #[event]
 pub struct RewardsFundedEvent {
     pub amount: u64,
-}
\ No newline at end of file
+    pub available_rewards: u64,
+    pub rewards_funded_until: i64,
+}
+
+#[event]
+pub struct RewardFundingSyncedEvent {
+    pub committed_rewards: u64,
+    pub available_rewards: u64,
+    pub rewards_funded_until: i64,
+}
+
+#[event]
+pub struct StakingPoolMigratedEvent {
+    pub pool_id: u8,
+    pub last_reward_update_time: i64,
+}
+
+#[event]
+pub struct UserStakeMigratedEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub lot_count: u8,
+}
+
+#[event]
+pub struct BoostRefreshedEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub boosted_balance: u64,
+    pub total_pool_boosted: u64,
+}
+
+#[event]
+pub struct EmissionScheduleUpdatedEvent {
+    pub kind: EmissionKind,
+    pub epoch_count: u8,
+    pub initial_rate: u64,
+    pub start_time: i64,
+    pub decay_period: i64,
+    pub decay_bps: u16,
+}
+
+#[event]
+pub struct EmissionScheduleClearedEvent {}
+
+#[event]
+pub struct AuthorityTransferProposedEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferAcceptedEvent {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelledEvent {
+    pub authority: Pubkey,
+    pub cancelled_authority: Pubkey,
+}
+
+#[event]
+pub struct RoleGrantedEvent {
+    pub member: Pubkey,
+    pub role: Role,
+    pub roles: u8,
+}
+
+#[event]
+pub struct RoleRevokedEvent {
+    pub member: Pubkey,
+    pub role: Role,
+    pub roles: u8,
+}
+
+#[event]
+pub struct ParameterChangeQueuedEvent {
+    pub change_id: u64,
+    pub change: ParameterChange,
+    pub eta: i64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct ParameterChangeExecutedEvent {
+    pub change_id: u64,
+    pub change: ParameterChange,
+}
+
+#[event]
+pub struct ParameterChangeCancelledEvent {
+    pub change_id: u64,
+    pub change: ParameterChange,
+}
+
+#[event]
+pub struct EconomicsMigratedEvent {
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct RewardStreamAddedEvent {
+    pub pool_id: u8,
+    pub stream_index: u8,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub authority: Pubkey,
+    pub rate_per_second: u64,
+}
+
+#[event]
+pub struct RewardStreamFundedEvent {
+    pub pool_id: u8,
+    pub stream_index: u8,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub available_rewards: u64,
+}
+
+#[event]
+pub struct RewardStreamRateUpdatedEvent {
+    pub pool_id: u8,
+    pub stream_index: u8,
+    pub new_rate: u64,
+}
+
+#[event]
+pub struct RewardCampaignStartedEvent {
+    pub pool_id: u8,
+    pub amount: u64,
+    pub leftover: u64,
+    pub rate_per_second: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct GovernanceInitializedEvent {
+    pub voting_period: i64,
+    pub quorum_bps: u16,
+    pub proposal_threshold: u64,
+    pub execution_grace_period: i64,
+    pub governance_authority: Pubkey,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    pub voting_ends_at: i64,
+    pub quorum_votes: u64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub user_stake: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+}
+
+#[event]
+pub struct ProposalCancelledEvent {
+    pub proposal_id: u64,
+}
+
+#[event]
+pub struct VoteRecordClosedEvent {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub user_stake: Pubkey,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+    pub action: ProposalAction,
+    pub executor: Pubkey,
+}
+
+#[event]
+pub struct TreasuryLimitsUpdatedEvent {
+    pub epoch_duration: i64,
+    pub epoch_cap: u64,
+    pub penalty_epoch_cap: u64,
+}
+
+#[event]
+pub struct TreasuryRecipientUpdatedEvent {
+    pub recipient: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct TreasuryWithdrawEvent {
+    pub treasurer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub epoch_start: i64,
+    pub spent_this_epoch: u64,
+    pub treasury_balance: u64,
+}
+
+#[event]
+pub struct PenaltyWithdrawEvent {
+    pub treasurer: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub epoch_start: i64,
+    pub penalty_spent_this_epoch: u64,
+    pub penalty_vault_balance: u64,
+}
+
+#[event]
+pub struct FeeDistributionUpdatedEvent {
+    pub recipients: Vec<FeeRecipient>,
+}
+
+#[event]
+pub struct FeeDistributionClearedEvent {}
+
+#[event]
+pub struct ReferralShareUpdatedEvent {
+    pub share_bps: u16,
+}
+
+#[event]
+pub struct ReferrerRegisteredEvent {
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralBoundEvent {
+    pub user: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralRewardAccruedEvent {
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimedEvent {
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct NftBoostConfiguredEvent {
+    pub pool_id: u8,
+    pub collection: Pubkey,
+    pub boost_bps: u16,
+}
+
+#[event]
+pub struct NftBoostChangedEvent {
+    pub user: Pubkey,
+    pub pool_id: u8,
+    pub nft_token_account: Pubkey,
+    pub active: bool,
+}
+
+#[event]
+pub struct LoyaltyTiersUpdatedEvent {
+    pub pool_id: u8,
+    pub tiers: Vec<LoyaltyTier>,
+}
@@ -1,27 +1,61 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
-use crate::context::{AdminControl, FundRewards};
-use crate::events::{PauseStateChangedEvent, EmergencyModeChangedEvent, RewardRateUpdatedEvent, RewardsFundedEvent};
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{AdminControl, RoleControl, UpdateRewardRate, AcceptAuthority, FundRewards, SyncRewardFunding};
+use crate::events::{
+    PauseStateChangedEvent, EmergencyModeChangedEvent, RewardRateUpdatedEvent, RewardsFundedEvent,
+    RewardFundingSyncedEvent,
+    AuthorityTransferProposedEvent, AuthorityTransferAcceptedEvent, AuthorityTransferCancelledEvent,
+};
+use crate::errors::EconomicsError;
+use crate::state::Role;
+use crate::utils::{
+    require_role, require_no_timelock, validate_emission_schedule, refresh_rewards_funded_until, settle_pools,
+};
 
-pub fn set_pause_state(ctx: Context<AdminControl>, is_paused: bool) -> Result<()> {
+pub fn set_pause_state(ctx: Context<RoleControl>, is_paused: bool) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Pauser,
+    )?;
     ctx.accounts.economics.is_paused = is_paused;
     
     emit!(PauseStateChangedEvent { is_paused });
     Ok(())
 }
 
-pub fn set_emergency_mode(ctx: Context<AdminControl>, emergency_mode: bool) -> Result<()> {
+pub fn set_emergency_mode(ctx: Context<RoleControl>, emergency_mode: bool) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Pauser,
+    )?;
     ctx.accounts.economics.emergency_mode = emergency_mode;
     
     emit!(EmergencyModeChangedEvent { emergency_mode });
     Ok(())
 }
 
-pub fn update_reward_rate(ctx: Context<AdminControl>, new_rate: u64) -> Result<()> {
+/// Changes the flat base rate from now on.
+///
+/// Remaining accounts: every active staking pool, writable, so time they have
+/// not yet settled still accrues at the old rate.
+pub fn update_reward_rate<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateRewardRate<'info>>,
+    new_rate: u64,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::RateManager,
+    )?;
+    require_no_timelock(&ctx.accounts.economics)?;
     let economics = &mut ctx.accounts.economics;
     
-    // Note: In a production system, you would want to update all active pools
-    // before changing the reward rate to ensure fairness
+    settle_pools(economics, ctx.remaining_accounts, ctx.accounts.emission_schedule.as_ref())?;
     economics.reward_rate_per_second = new_rate;
     
     emit!(RewardRateUpdatedEvent { new_rate });
@@ -29,14 +63,107 @@ pub fn update_reward_rate(ctx: Context<AdminControl>, new_rate: u64) -> Result<(
 }
 
 pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
-    let cpi_accounts = Transfer {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Treasurer,
+    )?;
+    validate_emission_schedule(&ctx.accounts.economics, ctx.accounts.emission_schedule.as_ref())?;
+
+    let vault_balance_before = ctx.accounts.reward_vault.amount;
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.authority_token_account.to_account_info(),
+        mint: ctx.accounts.reward_mint.to_account_info(),
         to: ctx.accounts.reward_vault.to_account_info(),
         authority: ctx.accounts.authority.to_account_info(),
     };
     let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.reward_mint.decimals)?;
+
+    // Report what the vault actually received after any transfer fee
+    ctx.accounts.reward_vault.reload()?;
+    let amount = ctx.accounts.reward_vault.amount.saturating_sub(vault_balance_before);
+
+    let economics = &mut ctx.accounts.economics;
+    economics.available_rewards = economics.available_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    refresh_rewards_funded_until(
+        economics,
+        ctx.accounts.emission_schedule.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    emit!(RewardsFundedEvent {
+        amount,
+        available_rewards: economics.available_rewards,
+        rewards_funded_until: economics.rewards_funded_until,
+    });
+    Ok(())
+}
+
+/// Recounts the available rewards from the vault balance, picking up tokens
+/// sent to the vault directly and funding held by accounts migrated from
+/// before solvency tracking. Anyone may call it.
+pub fn sync_reward_funding(ctx: Context<SyncRewardFunding>) -> Result<()> {
+    validate_emission_schedule(&ctx.accounts.economics, ctx.accounts.emission_schedule.as_ref())?;
+
+    let economics = &mut ctx.accounts.economics;
+    economics.available_rewards = ctx.accounts.reward_vault.amount
+        .saturating_sub(economics.committed_rewards)
+        .saturating_sub(economics.campaign_reserved);
+    refresh_rewards_funded_until(
+        economics,
+        ctx.accounts.emission_schedule.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    emit!(RewardFundingSyncedEvent {
+        committed_rewards: economics.committed_rewards,
+        available_rewards: economics.available_rewards,
+        rewards_funded_until: economics.rewards_funded_until,
+    });
+    Ok(())
+}
+
+/// Nominates a new authority; nothing changes until it signs `accept_authority`
+pub fn propose_authority(ctx: Context<AdminControl>, new_authority: Pubkey) -> Result<()> {
+    require_keys_neq!(new_authority, Pubkey::default(), EconomicsError::InvalidPendingAuthority);
 
-    emit!(RewardsFundedEvent { amount });
+    let economics = &mut ctx.accounts.economics;
+    economics.pending_authority = new_authority;
+
+    emit!(AuthorityTransferProposedEvent {
+        authority: economics.authority,
+        pending_authority: new_authority,
+    });
+    Ok(())
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let economics = &mut ctx.accounts.economics;
+    let previous_authority = economics.authority;
+    economics.authority = economics.pending_authority;
+    economics.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferAcceptedEvent {
+        previous_authority,
+        new_authority: economics.authority,
+    });
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn cancel_authority_transfer(ctx: Context<AdminControl>) -> Result<()> {
+    let economics = &mut ctx.accounts.economics;
+    require_keys_neq!(economics.pending_authority, Pubkey::default(), EconomicsError::NoPendingAuthority);
+
+    let cancelled_authority = economics.pending_authority;
+    economics.pending_authority = Pubkey::default();
+
+    emit!(AuthorityTransferCancelledEvent {
+        authority: economics.authority,
+        cancelled_authority,
+    });
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::context::RefreshBoost;
+use crate::events::BoostRefreshedEvent;
+use crate::errors::EconomicsError;
+use crate::utils::{
+    update_reward_accumulation, settle_user_rewards, sync_reward_debts, refresh_boosted_balance,
+    update_nft_boost,
+};
+
+/// Anyone may poke a position so its boost decays on schedule instead of
+/// staying at the level recorded when the owner last touched it. Passing the
+/// NFT accounts applies the pool's NFT boost, or removes it once the recorded
+/// token account no longer holds the NFT.
+pub fn refresh_boost(ctx: Context<RefreshBoost>) -> Result<()> {
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+
+    // Settle rewards earned at the old weight before re-weighting
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+    settle_user_rewards(user_stake, pool)?;
+
+    if let Some(nft_token_account) = ctx.accounts.nft_token_account.as_ref() {
+        update_nft_boost(pool, user_stake, nft_token_account, ctx.accounts.nft_metadata.as_deref())?;
+    }
+    refresh_boosted_balance(user_stake, pool, Clock::get()?.unix_timestamp)?;
+    sync_reward_debts(user_stake, pool)?;
+
+    emit!(BoostRefreshedEvent {
+        user: user_stake.user,
+        pool_id: user_stake.pool_id,
+        boosted_balance: user_stake.boosted_balance,
+        total_pool_boosted: pool.total_boosted,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::NotifyRewardAmount;
+use crate::events::RewardCampaignStartedEvent;
+use crate::errors::EconomicsError;
+use crate::state::{Role, StakingPool};
+use crate::utils::{require_role, update_reward_accumulation};
+
+/// Funds the pool's primary reward for `duration` seconds from now. The tokens
+/// are reserved for this pool alone; whatever the pool's reserve still holds
+/// is added to `amount`, and the pool emits the total evenly until the new
+/// end time.
+pub fn notify_reward_amount(
+    ctx: Context<NotifyRewardAmount>,
+    pool_id: u8,
+    amount: u64,
+    duration: i64,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Treasurer,
+    )?;
+    require!(
+        duration > 0 && duration <= StakingPool::MAX_CAMPAIGN_DURATION,
+        EconomicsError::InvalidRewardCampaign
+    );
+
+    // Settle the running campaign (or base emission) up to now
+    {
+        let economics = &mut ctx.accounts.economics;
+        let pool = &mut ctx.accounts.staking_pool;
+        update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+    }
+
+    let vault_balance_before = ctx.accounts.reward_vault.amount;
+    if amount > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.authority_token_account.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.reward_mint.decimals)?;
+        ctx.accounts.reward_vault.reload()?;
+    }
+    // Campaign what the vault actually received after any transfer fee
+    let amount = ctx.accounts.reward_vault.amount.saturating_sub(vault_balance_before);
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Includes what an earlier campaign left unemitted while nobody staked
+    let leftover = pool.campaign_reserved;
+    let reserved = leftover
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    let rate_per_second = (reserved as u128)
+        .checked_div(duration as u128)
+        .ok_or(EconomicsError::MathOverflow)?;
+    require!(rate_per_second > 0, EconomicsError::InvalidRewardCampaign);
+    let rate_per_second = u64::try_from(rate_per_second).map_err(|_| error!(EconomicsError::MathOverflow))?;
+
+    // Stretching leftover over a longer period lowers the rate, which the
+    // timelock must still protect stakers from
+    if economics.timelock_delay > 0 && pool.campaign_end_time > now {
+        require!(
+            rate_per_second >= pool.campaign_rate_per_second,
+            EconomicsError::TimelockActive
+        );
+    }
+
+    pool.campaign_rate_per_second = rate_per_second;
+    pool.campaign_end_time = now
+        .checked_add(duration)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // The leftover is reserved already; only the new tokens add to it
+    pool.campaign_reserved = reserved;
+    economics.campaign_reserved = economics.campaign_reserved
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    emit!(RewardCampaignStartedEvent {
+        pool_id,
+        amount,
+        leftover,
+        rate_per_second,
+        end_time: pool.campaign_end_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{Compound, EnableAutoCompound, DisableAutoCompound};
+use crate::events::{CompoundEvent, AutoCompoundChangedEvent};
+use crate::errors::EconomicsError;
+use crate::utils::{
+    update_reward_accumulation, settle_user_rewards, sync_reward_debts,
+    add_stake_lot, reactivate_position, refresh_boosted_balance, release_committed_rewards, record_stake_checkpoint,
+    validate_fee_distribution, distribute_governance_fee, FeeAccounts, credit_referral,
+};
+
+/// Restakes the caller's own net primary rewards
+pub fn compound<'info>(ctx: Context<'_, '_, 'info, 'info, Compound<'info>>) -> Result<()> {
+    require_keys_eq!(ctx.accounts.caller.key(), ctx.accounts.user_stake.user, EconomicsError::Unauthorized);
+    compound_position(ctx)
+}
+
+/// Restakes the net primary rewards of a position that opted into auto-compounding
+pub fn crank_compound<'info>(ctx: Context<'_, '_, 'info, 'info, Compound<'info>>) -> Result<()> {
+    let auto_compound = ctx.accounts.auto_compound
+        .as_ref()
+        .ok_or(EconomicsError::AutoCompoundDisabled)?;
+    require_keys_eq!(
+        auto_compound.user_stake,
+        ctx.accounts.user_stake.key(),
+        EconomicsError::AutoCompoundDisabled
+    );
+    compound_position(ctx)
+}
+
+pub fn enable_auto_compound(ctx: Context<EnableAutoCompound>) -> Result<()> {
+    let auto_compound = &mut ctx.accounts.auto_compound;
+    auto_compound.user_stake = ctx.accounts.user_stake.key();
+    auto_compound.user = ctx.accounts.user.key();
+    auto_compound.bump = ctx.bumps.auto_compound;
+
+    emit!(AutoCompoundChangedEvent {
+        user: auto_compound.user,
+        user_stake: auto_compound.user_stake,
+        enabled: true,
+    });
+    Ok(())
+}
+
+pub fn disable_auto_compound(ctx: Context<DisableAutoCompound>) -> Result<()> {
+    emit!(AutoCompoundChangedEvent {
+        user: ctx.accounts.user.key(),
+        user_stake: ctx.accounts.auto_compound.user_stake,
+        enabled: false,
+    });
+    Ok(())
+}
+
+/// Moves the position's net primary rewards from the reward vault into the
+/// stake vault and credits them as a new lot. Stream rewards stay claimable.
+///
+/// Remaining accounts: the token accounts of the fee split's external recipients.
+fn compound_position<'info>(ctx: Context<'_, '_, 'info, 'info, Compound<'info>>) -> Result<()> {
+    require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+    validate_fee_distribution(&ctx.accounts.economics, ctx.accounts.fee_distribution.as_ref())?;
+
+    {
+        let economics = &mut ctx.accounts.economics;
+        let pool = &mut ctx.accounts.staking_pool;
+        update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+        settle_user_rewards(&mut ctx.accounts.user_stake, pool)?;
+    }
+
+    let total_rewards = ctx.accounts.user_stake.pending_rewards;
+    require!(total_rewards > 0, EconomicsError::NoRewardsToClaim);
+
+    // Calculate governance fee
+    let governance_fee = total_rewards
+        .checked_mul(ctx.accounts.economics.governance_fee_bps as u64)
+        .ok_or(EconomicsError::MathOverflow)?
+        .checked_div(10000)
+        .ok_or(EconomicsError::MathOverflow)?;
+    let net_reward = total_rewards
+        .checked_sub(governance_fee)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // The referrer's cut comes out of the governance fee and stays committed
+    // in the vault until the referrer claims it
+    let referral_reward = credit_referral(
+        &ctx.accounts.economics,
+        &ctx.accounts.referral,
+        ctx.accounts.referrer_account.as_mut(),
+        ctx.accounts.user_stake.user,
+        governance_fee,
+    )?;
+
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // Pay the governance fee to the treasury or across the fee split
+    distribute_governance_fee(
+        governance_fee.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+        ctx.accounts.fee_distribution.as_deref(),
+        FeeAccounts {
+            vault: &ctx.accounts.reward_vault.to_account_info(),
+            treasury_vault: &ctx.accounts.treasury_vault.to_account_info(),
+            mint: &ctx.accounts.stake_mint,
+            authority: &ctx.accounts.economics.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            recipients: ctx.remaining_accounts,
+        },
+        signer_seeds,
+    )?;
+
+    // Move the net reward into the stake vault, crediting what actually arrives
+    let vault_balance_before = ctx.accounts.stake_vault.amount;
+    let stake_cpi_accounts = TransferChecked {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.economics.to_account_info(),
+    };
+    let stake_cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        stake_cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(stake_cpi_ctx, net_reward, ctx.accounts.stake_mint.decimals)?;
+    ctx.accounts.stake_vault.reload()?;
+    let compounded = ctx.accounts.stake_vault.amount
+        .checked_sub(vault_balance_before)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(pool.total_staked + compounded <= pool.max_capacity, EconomicsError::PoolCapacityExceeded);
+    require!(
+        user_stake.total_staked + compounded <= economics.max_stake_amount,
+        EconomicsError::ExceedsMaximumStake
+    );
+
+    // Rewards are paid out of the position and straight back in
+    user_stake.pending_rewards = 0;
+    user_stake.total_rewards_claimed = user_stake.total_rewards_claimed
+        .checked_add(net_reward)
+        .ok_or(EconomicsError::MathOverflow)?;
+    user_stake.last_claim_time = now;
+    economics.total_rewards_distributed = economics.total_rewards_distributed
+        .checked_add(total_rewards)
+        .ok_or(EconomicsError::MathOverflow)?;
+    release_committed_rewards(
+        economics,
+        total_rewards.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+    );
+
+    reactivate_position(economics, pool, user_stake, now)?;
+
+    // Compounded rewards follow the pool's minimum period like any unlocked deposit
+    let unlock_time = now
+        .checked_add(pool.min_stake_period)
+        .ok_or(EconomicsError::MathOverflow)?;
+    let lot_id = add_stake_lot(user_stake, compounded, now, unlock_time)?;
+    user_stake.total_staked = user_stake.total_staked
+        .checked_add(compounded)
+        .ok_or(EconomicsError::MathOverflow)?;
+    user_stake.last_stake_time = now;
+    refresh_boosted_balance(user_stake, pool, now)?;
+    sync_reward_debts(user_stake, pool)?;
+
+    pool.total_staked = pool.total_staked
+        .checked_add(compounded)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.total_staked = economics.total_staked
+        .checked_add(compounded)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // Only the owner's own compounds enter the voting history; a crank could
+    // otherwise fill it and push out checkpoints an open proposal still needs.
+    // Cranked stake counts from the owner's next recorded change.
+    if ctx.accounts.caller.key() == user_stake.user {
+        let checkpoints = ctx.accounts.checkpoints.as_mut().ok_or(EconomicsError::CheckpointsRequired)?;
+        let bump = ctx.bumps.checkpoints.ok_or(EconomicsError::CheckpointsRequired)?;
+        record_stake_checkpoint(checkpoints, user_stake, bump, now);
+    }
+
+    emit!(CompoundEvent {
+        user: user_stake.user,
+        pool_id: user_stake.pool_id,
+        caller: ctx.accounts.caller.key(),
+        gross_reward: total_rewards,
+        governance_fee,
+        compounded,
+        lot_id,
+        total_user_stake: user_stake.total_staked,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::context::{SetEmissionSchedule, ClearEmissionSchedule};
+use crate::events::{EmissionScheduleUpdatedEvent, EmissionScheduleClearedEvent};
+use crate::errors::EconomicsError;
+use crate::state::{EmissionEpoch, EmissionKind};
+use crate::utils::{require_no_timelock, settle_pools, validate_emission_epochs};
+
+// Remaining accounts for every instruction here: every active staking pool,
+// writable, so time they have not yet settled accrues under the old emission.
+
+pub fn set_emission_epochs<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetEmissionSchedule<'info>>,
+    epochs: Vec<EmissionEpoch>,
+) -> Result<()> {
+    require_no_timelock(&ctx.accounts.economics)?;
+    validate_emission_epochs(&epochs)?;
+    settle_under_active_schedule(ctx.accounts, ctx.remaining_accounts)?;
+
+    let schedule = &mut ctx.accounts.emission_schedule;
+    schedule.economics = ctx.accounts.economics.key();
+    schedule.set_epochs(epochs);
+    schedule.bump = ctx.bumps.emission_schedule;
+
+    ctx.accounts.economics.emission_schedule = schedule.key();
+
+    emit!(EmissionScheduleUpdatedEvent {
+        kind: EmissionKind::Epochs,
+        epoch_count: schedule.epochs.len() as u8,
+        initial_rate: 0,
+        start_time: schedule.epochs[0].start_time,
+        decay_period: 0,
+        decay_bps: 0,
+    });
+    Ok(())
+}
+
+pub fn set_emission_decay<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetEmissionSchedule<'info>>,
+    initial_rate: u64,
+    start_time: i64,
+    decay_period: i64,
+    decay_bps: u16,
+) -> Result<()> {
+    require_no_timelock(&ctx.accounts.economics)?;
+    require!(decay_period > 0, EconomicsError::InvalidEmissionSchedule);
+    require!(decay_bps <= 10000, EconomicsError::InvalidEmissionSchedule);
+    settle_under_active_schedule(ctx.accounts, ctx.remaining_accounts)?;
+
+    let schedule = &mut ctx.accounts.emission_schedule;
+    schedule.economics = ctx.accounts.economics.key();
+    schedule.set_decay(initial_rate, start_time, decay_period, decay_bps);
+    schedule.bump = ctx.bumps.emission_schedule;
+
+    ctx.accounts.economics.emission_schedule = schedule.key();
+
+    emit!(EmissionScheduleUpdatedEvent {
+        kind: EmissionKind::Decay,
+        epoch_count: 0,
+        initial_rate,
+        start_time,
+        decay_period,
+        decay_bps,
+    });
+    Ok(())
+}
+
+pub fn clear_emission_schedule<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ClearEmissionSchedule<'info>>,
+) -> Result<()> {
+    require_no_timelock(&ctx.accounts.economics)?;
+    settle_pools(&mut ctx.accounts.economics, ctx.remaining_accounts, ctx.accounts.emission_schedule.as_ref())?;
+
+    // Falls back to the flat `reward_rate_per_second`
+    ctx.accounts.economics.emission_schedule = Pubkey::default();
+
+    emit!(EmissionScheduleClearedEvent {});
+    Ok(())
+}
+
+/// The schedule account is rewritten in place, so it only counts as the old
+/// schedule while it is the active one
+fn settle_under_active_schedule<'info>(
+    accounts: &mut SetEmissionSchedule<'info>,
+    pool_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let active_schedule = (accounts.economics.emission_schedule != Pubkey::default())
+        .then_some(&accounts.emission_schedule);
+    settle_pools(&mut accounts.economics, pool_accounts, active_schedule)
+}
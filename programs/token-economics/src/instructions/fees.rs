@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::context::{AdminControl, SetFeeDistribution};
+use crate::events::{FeeDistributionUpdatedEvent, FeeDistributionClearedEvent};
+use crate::errors::EconomicsError;
+use crate::state::{FeeDistribution, FeeDestination, FeeRecipient};
+
+/// Splits future governance fees between the given recipients.
+///
+/// Remaining accounts: the token account of every `FeeDestination::TokenAccount`
+/// recipient, in order, so a bad address cannot brick later claims.
+pub fn set_fee_distribution<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetFeeDistribution<'info>>,
+    recipients: Vec<FeeRecipient>,
+) -> Result<()> {
+    require!(
+        !recipients.is_empty() && recipients.len() <= FeeDistribution::MAX_RECIPIENTS,
+        EconomicsError::InvalidFeeDistribution
+    );
+    require!(
+        recipients.iter().all(|recipient| recipient.bps > 0),
+        EconomicsError::InvalidFeeDistribution
+    );
+    let total_bps: u32 = recipients.iter().map(|recipient| recipient.bps as u32).sum();
+    require!(total_bps == 10000, EconomicsError::InvalidFeeDistribution);
+    for (index, recipient) in recipients.iter().enumerate() {
+        require!(
+            recipients[..index].iter().all(|other| other.destination != recipient.destination),
+            EconomicsError::InvalidFeeDistribution
+        );
+    }
+
+    let token_accounts: Vec<Pubkey> = recipients
+        .iter()
+        .filter_map(|recipient| match recipient.destination {
+            FeeDestination::TokenAccount { address } => Some(address),
+            _ => None,
+        })
+        .collect();
+    require!(
+        ctx.remaining_accounts.len() == token_accounts.len(),
+        EconomicsError::InvalidFeeRecipientAccounts
+    );
+    for (address, account_info) in token_accounts.iter().zip(ctx.remaining_accounts) {
+        require_keys_eq!(account_info.key(), *address, EconomicsError::InvalidFeeRecipientAccounts);
+        let token_account = InterfaceAccount::<TokenAccount>::try_from(account_info)?;
+        require_keys_eq!(
+            token_account.mint,
+            ctx.accounts.economics.reward_mint,
+            EconomicsError::InvalidFeeRecipientAccounts
+        );
+    }
+
+    let fee_distribution = &mut ctx.accounts.fee_distribution;
+    fee_distribution.economics = ctx.accounts.economics.key();
+    fee_distribution.recipients = recipients;
+    fee_distribution.bump = ctx.bumps.fee_distribution;
+
+    ctx.accounts.economics.fee_distribution = fee_distribution.key();
+
+    emit!(FeeDistributionUpdatedEvent {
+        recipients: fee_distribution.recipients.clone(),
+    });
+    Ok(())
+}
+
+pub fn clear_fee_distribution(ctx: Context<AdminControl>) -> Result<()> {
+    // Falls back to sending the whole fee to `treasury_vault`
+    ctx.accounts.economics.fee_distribution = Pubkey::default();
+
+    emit!(FeeDistributionClearedEvent {});
+    Ok(())
+}
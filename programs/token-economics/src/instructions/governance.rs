@@ -0,0 +1,322 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::InstructionData;
+use crate::context::{
+    InitializeGovernance, CreateProposal, CastVote, CancelProposal, ExecuteProposal, CloseVoteRecord,
+    CheckpointStake, VotingPowerAt,
+};
+use crate::events::{
+    GovernanceInitializedEvent, ProposalCreatedEvent, VoteCastEvent, ProposalCancelledEvent, ProposalExecutedEvent,
+    VoteRecordClosedEvent,
+};
+use crate::errors::EconomicsError;
+use crate::state::{GovernanceConfig, PendingChange, ProposalAction};
+use crate::utils::record_stake_checkpoint;
+
+pub fn initialize_governance(
+    ctx: Context<InitializeGovernance>,
+    voting_period: i64,
+    quorum_bps: u16,
+    proposal_threshold: u64,
+    execution_grace_period: i64,
+) -> Result<()> {
+    require!(
+        voting_period > 0 && voting_period <= GovernanceConfig::MAX_VOTING_PERIOD,
+        EconomicsError::InvalidGovernanceConfig
+    );
+    require!(quorum_bps > 0 && quorum_bps <= 10000, EconomicsError::InvalidGovernanceConfig);
+    require!(
+        execution_grace_period > 0 && execution_grace_period <= GovernanceConfig::MAX_EXECUTION_GRACE_PERIOD,
+        EconomicsError::InvalidGovernanceConfig
+    );
+
+    let governance = &mut ctx.accounts.governance;
+    governance.economics = ctx.accounts.economics.key();
+    governance.voting_period = voting_period;
+    governance.quorum_bps = quorum_bps;
+    governance.proposal_threshold = proposal_threshold;
+    governance.execution_grace_period = execution_grace_period;
+    governance.next_proposal_id = 0;
+    governance.bump = ctx.bumps.governance;
+    governance.authority_bump = ctx.bumps.governance_authority;
+
+    emit!(GovernanceInitializedEvent {
+        voting_period,
+        quorum_bps,
+        proposal_threshold,
+        execution_grace_period,
+        governance_authority: ctx.accounts.governance_authority.key(),
+    });
+    Ok(())
+}
+
+/// Opens a proposal; quorum is fixed against the total stake at this moment
+pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let governance = &mut ctx.accounts.governance;
+
+    let proposer_power = ctx.accounts.checkpoints.voting_power_at(now)?;
+    require!(
+        proposer_power > 0 && proposer_power >= governance.proposal_threshold,
+        EconomicsError::InsufficientVotingPower
+    );
+
+    let quorum_votes = (ctx.accounts.economics.total_staked as u128)
+        .checked_mul(governance.quorum_bps as u128)
+        .ok_or(EconomicsError::MathOverflow)?
+        .checked_div(10000)
+        .ok_or(EconomicsError::MathOverflow)? as u64;
+    let voting_ends_at = now
+        .checked_add(governance.voting_period)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    let proposal_id = governance.next_proposal_id;
+    governance.next_proposal_id = proposal_id.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.proposal_id = proposal_id;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.action = action.clone();
+    proposal.created_at = now;
+    proposal.voting_ends_at = voting_ends_at;
+    proposal.for_votes = 0;
+    proposal.against_votes = 0;
+    proposal.quorum_votes = quorum_votes;
+    proposal.executed = false;
+    proposal.cancelled = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    emit!(ProposalCreatedEvent {
+        proposal_id,
+        proposer: proposal.proposer,
+        action,
+        voting_ends_at,
+        quorum_votes,
+    });
+    Ok(())
+}
+
+/// Votes with one position's stake; a user staked in several pools votes once per position
+pub fn cast_vote(ctx: Context<CastVote>, proposal_id: u64, support: bool) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed && !proposal.cancelled, EconomicsError::ProposalFinalized);
+    require!(Clock::get()?.unix_timestamp < proposal.voting_ends_at, EconomicsError::VotingClosed);
+
+    let weight = ctx.accounts.checkpoints.voting_power_at(proposal.created_at)?;
+    require!(weight > 0, EconomicsError::InsufficientVotingPower);
+
+    if support {
+        proposal.for_votes = proposal.for_votes.checked_add(weight).ok_or(EconomicsError::MathOverflow)?;
+    } else {
+        proposal.against_votes = proposal.against_votes.checked_add(weight).ok_or(EconomicsError::MathOverflow)?;
+    }
+
+    let vote_record = &mut ctx.accounts.vote_record;
+    vote_record.proposal = proposal.key();
+    vote_record.user_stake = ctx.accounts.user_stake.key();
+    vote_record.voter = ctx.accounts.voter.key();
+    vote_record.support = support;
+    vote_record.weight = weight;
+    vote_record.bump = ctx.bumps.vote_record;
+
+    emit!(VoteCastEvent {
+        proposal_id,
+        voter: vote_record.voter,
+        user_stake: vote_record.user_stake,
+        support,
+        weight,
+    });
+    Ok(())
+}
+
+/// Withdraws a proposal while it is still being voted on
+pub fn cancel_proposal(ctx: Context<CancelProposal>, proposal_id: u64) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed && !proposal.cancelled, EconomicsError::ProposalFinalized);
+    require!(Clock::get()?.unix_timestamp < proposal.voting_ends_at, EconomicsError::VotingClosed);
+    proposal.cancelled = true;
+
+    emit!(ProposalCancelledEvent { proposal_id });
+    Ok(())
+}
+
+/// Carries out a passed proposal (permissionless). Parameter changes, treasury
+/// spends and the authority hand-over go through the program's own admin
+/// instructions by CPI, signed by the governance authority, so they get the
+/// same role, timelock and spending-limit checks as any other caller.
+pub fn execute_proposal(ctx: Context<ExecuteProposal>, proposal_id: u64) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require!(!proposal.executed && !proposal.cancelled, EconomicsError::ProposalFinalized);
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= proposal.voting_ends_at, EconomicsError::VotingStillOpen);
+    // A passed proposal left unexecuted cannot be revived long after the vote
+    let execution_deadline = proposal.voting_ends_at
+        .checked_add(ctx.accounts.governance.execution_grace_period)
+        .ok_or(EconomicsError::MathOverflow)?;
+    require!(now <= execution_deadline, EconomicsError::ProposalExpired);
+    require!(proposal.has_passed(), EconomicsError::ProposalNotPassed);
+    let action = proposal.action.clone();
+
+    let authority_seeds = &[b"governance_authority".as_ref(), &[ctx.accounts.governance.authority_bump]];
+    let authority_signer = &[&authority_seeds[..]];
+
+    match action.clone() {
+        ProposalAction::Parameter { change } => {
+            let pending_change = ctx.accounts.pending_change
+                .as_ref()
+                .ok_or(EconomicsError::InvalidProposalAccounts)?;
+
+            // The governance authority holds no data, so it can pay the pending
+            // change's rent once the executor has topped it up
+            let rent = Rent::get()?.minimum_balance(PendingChange::LEN);
+            let balance = ctx.accounts.governance_authority.lamports();
+            if balance < rent {
+                let top_up_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.executor.to_account_info(),
+                        to: ctx.accounts.governance_authority.to_account_info(),
+                    },
+                );
+                system_program::transfer(top_up_ctx, rent - balance)?;
+            }
+
+            let queue_ix = Instruction {
+                program_id: crate::ID,
+                accounts: crate::accounts::QueueParameterChange {
+                    economics: ctx.accounts.economics.key(),
+                    pending_change: pending_change.key(),
+                    role: ctx.accounts.role.as_ref().map(|role| role.key()),
+                    authority: ctx.accounts.governance_authority.key(),
+                    system_program: ctx.accounts.system_program.key(),
+                }.to_account_metas(None),
+                data: crate::instruction::QueueParameterChange { change }.data(),
+            };
+            invoke_signed(
+                &queue_ix,
+                &[
+                    ctx.accounts.economics.to_account_info(),
+                    pending_change.to_account_info(),
+                    ctx.accounts.role.as_ref()
+                        .map_or_else(|| ctx.accounts.economics_program.to_account_info(), |role| role.to_account_info()),
+                    ctx.accounts.governance_authority.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.economics_program.to_account_info(),
+                ],
+                authority_signer,
+            )?;
+        }
+        ProposalAction::TreasurySpend { recipient, amount } => {
+            // Spends go through `withdraw_treasury` so the allowlist and epoch cap apply
+            let (Some(treasury_config), Some(treasury_vault), Some(recipient_account), Some(reward_mint), Some(token_program)) = (
+                ctx.accounts.treasury_config.as_ref(),
+                ctx.accounts.treasury_vault.as_ref(),
+                ctx.accounts.recipient.as_ref(),
+                ctx.accounts.reward_mint.as_ref(),
+                ctx.accounts.token_program.as_ref(),
+            ) else {
+                return err!(EconomicsError::InvalidProposalAccounts);
+            };
+            require_keys_eq!(recipient_account.key(), recipient, EconomicsError::InvalidProposalAccounts);
+
+            let withdraw_ix = Instruction {
+                program_id: crate::ID,
+                accounts: crate::accounts::WithdrawTreasury {
+                    economics: ctx.accounts.economics.key(),
+                    treasury_config: treasury_config.key(),
+                    role: ctx.accounts.role.as_ref().map(|role| role.key()),
+                    authority: ctx.accounts.governance_authority.key(),
+                    treasury_vault: treasury_vault.key(),
+                    recipient,
+                    reward_mint: reward_mint.key(),
+                    token_program: token_program.key(),
+                }.to_account_metas(None),
+                data: crate::instruction::WithdrawTreasury { amount }.data(),
+            };
+            invoke_signed(
+                &withdraw_ix,
+                &[
+                    ctx.accounts.economics.to_account_info(),
+                    treasury_config.to_account_info(),
+                    ctx.accounts.role.as_ref()
+                        .map_or_else(|| ctx.accounts.economics_program.to_account_info(), |role| role.to_account_info()),
+                    ctx.accounts.governance_authority.to_account_info(),
+                    treasury_vault.to_account_info(),
+                    recipient_account.to_account_info(),
+                    reward_mint.to_account_info(),
+                    token_program.to_account_info(),
+                    ctx.accounts.economics_program.to_account_info(),
+                ],
+                authority_signer,
+            )?;
+        }
+        ProposalAction::AcceptAuthority => {
+            let accept_ix = Instruction {
+                program_id: crate::ID,
+                accounts: crate::accounts::AcceptAuthority {
+                    economics: ctx.accounts.economics.key(),
+                    new_authority: ctx.accounts.governance_authority.key(),
+                }.to_account_metas(None),
+                data: crate::instruction::AcceptAuthority {}.data(),
+            };
+            invoke_signed(
+                &accept_ix,
+                &[
+                    ctx.accounts.economics.to_account_info(),
+                    ctx.accounts.governance_authority.to_account_info(),
+                    ctx.accounts.economics_program.to_account_info(),
+                ],
+                authority_signer,
+            )?;
+        }
+    }
+
+    // The CPI wrote the economics account; drop the stale copy before exit
+    ctx.accounts.economics.reload()?;
+    ctx.accounts.proposal.executed = true;
+
+    emit!(ProposalExecutedEvent {
+        proposal_id,
+        action,
+        executor: ctx.accounts.executor.key(),
+    });
+    Ok(())
+}
+
+/// Returns a vote record's rent to the voter once it can no longer guard
+/// against a second vote
+pub fn close_vote_record(ctx: Context<CloseVoteRecord>, proposal_id: u64) -> Result<()> {
+    let proposal = &ctx.accounts.proposal;
+    require!(
+        proposal.executed || proposal.cancelled || Clock::get()?.unix_timestamp >= proposal.voting_ends_at,
+        EconomicsError::VotingStillOpen
+    );
+
+    emit!(VoteRecordClosedEvent {
+        proposal_id,
+        voter: ctx.accounts.vote_record.voter,
+        user_stake: ctx.accounts.vote_record.user_stake,
+    });
+    Ok(())
+}
+
+/// Starts the voting history of a position that predates checkpoints
+/// (permissionless); its stake counts for proposals opened afterwards. Only
+/// an empty history can be started, so callers cannot flood it.
+pub fn checkpoint_stake(ctx: Context<CheckpointStake>) -> Result<()> {
+    require!(ctx.accounts.checkpoints.checkpoints.is_empty(), EconomicsError::CheckpointHistoryExists);
+    record_stake_checkpoint(
+        &mut ctx.accounts.checkpoints,
+        &ctx.accounts.user_stake,
+        ctx.bumps.checkpoints,
+        Clock::get()?.unix_timestamp,
+    );
+    Ok(())
+}
+
+/// Voting power of a position at `timestamp`, for clients to simulate
+pub fn voting_power_at(ctx: Context<VotingPowerAt>, timestamp: i64) -> Result<u64> {
+    ctx.accounts.checkpoints.voting_power_at(timestamp)
+}
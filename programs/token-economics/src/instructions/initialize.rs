@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 use crate::context::{InitializeEconomics, CreateStakingPool};
 use crate::events::{PoolCreatedEvent};
 use crate::errors::EconomicsError;
+use crate::state::Role;
+use crate::utils::{validate_mint_extensions, require_role, refresh_rewards_funded_until};
 
 pub fn initialize_economics(
     ctx: Context<InitializeEconomics>,
@@ -13,6 +15,8 @@ pub fn initialize_economics(
     require!(governance_fee_bps <= 10000, EconomicsError::InvalidFeeRate);
     require!(min_stake_amount > 0, EconomicsError::InvalidStakeAmount);
     require!(max_stake_amount >= min_stake_amount, EconomicsError::InvalidStakeAmount);
+    validate_mint_extensions(&ctx.accounts.stake_mint)?;
+    validate_mint_extensions(&ctx.accounts.reward_mint)?;
 
     let economics = &mut ctx.accounts.economics;
     let clock = Clock::get()?;
@@ -37,6 +41,11 @@ pub fn initialize_economics(
     economics.accumulated_reward_per_token = 0;
     economics.active_stakers = 0;
     
+    // Nothing is funded yet, so accrual waits for `fund_rewards`
+    economics.available_rewards = 0;
+    economics.committed_rewards = 0;
+    refresh_rewards_funded_until(economics, None, clock.unix_timestamp)?;
+    
     // System flags
     economics.is_paused = false;
     economics.emergency_mode = false;
@@ -55,6 +64,12 @@ pub fn create_staking_pool(
     min_stake_period: i64,
     max_capacity: u64,
 ) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
     require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
     require!(reward_multiplier > 0 && reward_multiplier <= 1000, EconomicsError::InvalidMultiplier);
     require!(min_stake_period >= 0, EconomicsError::InvalidStakePeriod);
@@ -72,6 +87,8 @@ pub fn create_staking_pool(
     pool.created_at = clock.unix_timestamp;
     pool.is_active = true;
     pool.bump = ctx.bumps.staking_pool;
+    pool.last_reward_update_time = clock.unix_timestamp;
+    ctx.accounts.economics.set_pool(pool_id, true);
 
     emit!(PoolCreatedEvent {
         pool_id,
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::context::{MigrateEconomics, MigrateStakingPool, MigrateUserStake};
+use crate::events::{EconomicsMigratedEvent, StakingPoolMigratedEvent, UserStakeMigratedEvent};
+use crate::errors::EconomicsError;
+use crate::state::{TokenEconomics, StakingPool, UserStake};
+use crate::utils::{grow_account, add_stake_lot, commit_legacy_rewards};
+
+/// Extends the economics account created by an older program version to the
+/// current layout. Appended fields are zero-filled, which leaves every newer
+/// feature in its disabled state.
+pub fn migrate_economics(ctx: Context<MigrateEconomics>) -> Result<()> {
+    let economics_info = ctx.accounts.economics.to_account_info();
+    grow_account(
+        &economics_info,
+        TokenEconomics::LEN,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let economics = TokenEconomics::try_deserialize(&mut &economics_info.try_borrow_data()?[..])?;
+    require_keys_eq!(economics.authority, ctx.accounts.authority.key(), EconomicsError::Unauthorized);
+
+    emit!(EconomicsMigratedEvent {
+        authority: economics.authority,
+    });
+
+    Ok(())
+}
+
+/// Extends a staking pool created by an older program version to the current
+/// layout. Appended fields are zero-filled by the resize and then backfilled.
+pub fn migrate_staking_pool(ctx: Context<MigrateStakingPool>, pool_id: u8) -> Result<()> {
+    let pool_info = ctx.accounts.staking_pool.to_account_info();
+    grow_account(
+        &pool_info,
+        StakingPool::LEN,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let mut pool = StakingPool::try_deserialize(&mut &pool_info.try_borrow_data()?[..])?;
+
+    // Legacy pools accrued against the shared economics clock; start their own
+    // clock from there so no elapsed time is lost or double counted.
+    if pool.last_reward_update_time == 0 {
+        pool.last_reward_update_time = ctx.accounts.economics.last_reward_update_time;
+    }
+
+    // Legacy positions carry no boost, so their boosted total is the raw total
+    if pool.total_boosted == 0 {
+        pool.total_boosted = pool.total_staked;
+    }
+
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    // Legacy pools predate the pool registry; settlements must cover them too
+    ctx.accounts.economics.set_pool(pool_id, true);
+
+    emit!(StakingPoolMigratedEvent {
+        pool_id,
+        last_reward_update_time: pool.last_reward_update_time,
+    });
+
+    Ok(())
+}
+
+/// Extends a user stake created by an older program version to the current layout.
+pub fn migrate_user_stake(ctx: Context<MigrateUserStake>, pool_id: u8) -> Result<()> {
+    let user_stake_info = ctx.accounts.user_stake.to_account_info();
+    let is_legacy = user_stake_info.data_len() < UserStake::LEN;
+    grow_account(
+        &user_stake_info,
+        UserStake::LEN,
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    let mut user_stake = UserStake::try_deserialize(&mut &user_stake_info.try_borrow_data()?[..])?;
+    require_keys_eq!(user_stake.user, ctx.accounts.user.key(), EconomicsError::Unauthorized);
+
+    // Every legacy position counts as open, including emptied ones that still
+    // hold pending rewards; the size check keeps a repeated call from counting twice
+    if is_legacy {
+        let pool = &mut ctx.accounts.staking_pool;
+        pool.open_positions = pool.open_positions
+            .checked_add(1)
+            .ok_or(EconomicsError::MathOverflow)?;
+
+        // Rewards the position was already owed stay in the vault for it
+        commit_legacy_rewards(&mut ctx.accounts.economics, user_stake.pending_rewards)?;
+    }
+
+    // Legacy positions were a single balance locked from the last deposit;
+    // carry that over as one lot with the same unlock time.
+    if user_stake.lots.is_empty() && user_stake.total_staked > 0 {
+        let unlock_time = user_stake.last_stake_time
+            .checked_add(ctx.accounts.staking_pool.min_stake_period)
+            .ok_or(EconomicsError::MathOverflow)?;
+        let (amount, deposit_time) = (user_stake.total_staked, user_stake.last_stake_time);
+        add_stake_lot(&mut user_stake, amount, deposit_time, unlock_time)?;
+    }
+
+    // Matches the unboosted weight the pool migration counted for this position
+    if user_stake.boosted_balance == 0 {
+        user_stake.boosted_balance = user_stake.total_staked;
+    }
+
+    user_stake.try_serialize(&mut &mut user_stake_info.try_borrow_mut_data()?[..])?;
+
+    emit!(UserStakeMigratedEvent {
+        user: user_stake.user,
+        pool_id,
+        lot_count: user_stake.lots.len() as u8,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,37 @@
 pub mod initialize;
+pub mod pool;
+pub mod campaign;
 pub mod stake;
+pub mod unbonding;
+pub mod boost;
 pub mod rewards;
+pub mod compound;
+pub mod streams;
 pub mod admin;
+pub mod treasury;
+pub mod fees;
+pub mod referral;
+pub mod roles;
+pub mod timelock;
+pub mod governance;
+pub mod emission;
+pub mod migrate;
 
 pub use initialize::*;
+pub use pool::*;
+pub use campaign::*;
 pub use stake::*;
+pub use unbonding::*;
+pub use boost::*;
 pub use rewards::*;
-pub use admin::*;
\ No newline at end of file
+pub use compound::*;
+pub use streams::*;
+pub use admin::*;
+pub use treasury::*;
+pub use fees::*;
+pub use referral::*;
+pub use roles::*;
+pub use timelock::*;
+pub use governance::*;
+pub use emission::*;
+pub use migrate::*;
\ No newline at end of file
@@ -0,0 +1,268 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TransferChecked};
+use crate::context::{UpdateStakingPool, CloseStakingPool};
+use crate::events::{
+    StakingPoolUpdatedEvent, StakingPoolDeactivatedEvent, StakingPoolReactivatedEvent, StakingPoolClosedEvent,
+    EarlyExitPenaltyUpdatedEvent, NftBoostConfiguredEvent, LoyaltyTiersUpdatedEvent,
+};
+use crate::errors::EconomicsError;
+use crate::state::{Role, StakingPool, PenaltyMode, PenaltyDestination, LoyaltyTier};
+use crate::utils::{
+    require_role, require_no_timelock, update_reward_accumulation, validate_penalty_config, validate_loyalty_tiers,
+    validate_emission_schedule, refresh_rewards_funded_until,
+};
+
+/// Changes pool parameters; `None` keeps the current value
+pub fn update_staking_pool(
+    ctx: Context<UpdateStakingPool>,
+    pool_id: u8,
+    reward_multiplier: Option<u16>,
+    min_stake_period: Option<i64>,
+    max_capacity: Option<u64>,
+    unbonding_period: Option<i64>,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+    require_no_timelock(&ctx.accounts.economics)?;
+    if let Some(reward_multiplier) = reward_multiplier {
+        require!(reward_multiplier > 0 && reward_multiplier <= 1000, EconomicsError::InvalidMultiplier);
+    }
+    if let Some(min_stake_period) = min_stake_period {
+        require!(min_stake_period >= 0, EconomicsError::InvalidStakePeriod);
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        require!(
+            (0..=StakingPool::MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+            EconomicsError::InvalidStakePeriod
+        );
+    }
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+
+    // Settle accrual under the old parameters before switching
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+
+    if let Some(reward_multiplier) = reward_multiplier {
+        pool.reward_multiplier = reward_multiplier;
+    }
+    if let Some(min_stake_period) = min_stake_period {
+        pool.min_stake_period = min_stake_period;
+    }
+    if let Some(max_capacity) = max_capacity {
+        pool.max_capacity = max_capacity;
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        pool.unbonding_period = unbonding_period;
+    }
+
+    emit!(StakingPoolUpdatedEvent {
+        pool_id,
+        reward_multiplier: pool.reward_multiplier,
+        min_stake_period: pool.min_stake_period,
+        max_capacity: pool.max_capacity,
+        unbonding_period: pool.unbonding_period,
+    });
+    Ok(())
+}
+
+/// Configures whether locked stake can leave early and at what cost
+pub fn set_early_exit_penalty(
+    ctx: Context<UpdateStakingPool>,
+    pool_id: u8,
+    penalty_mode: PenaltyMode,
+    penalty_bps: u16,
+    penalty_destination: PenaltyDestination,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+    require_no_timelock(&ctx.accounts.economics)?;
+    validate_penalty_config(&ctx.accounts.economics, penalty_bps, penalty_destination)?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.penalty_mode = penalty_mode;
+    pool.penalty_bps = penalty_bps;
+    pool.penalty_destination = penalty_destination;
+
+    emit!(EarlyExitPenaltyUpdatedEvent {
+        pool_id,
+        penalty_mode,
+        penalty_bps,
+        penalty_destination,
+    });
+    Ok(())
+}
+
+/// Offers an extra weight to holders of a verified collection's NFTs; the
+/// default collection turns the boost off. Positions pick up the change the
+/// next time they are re-weighted.
+pub fn set_nft_boost(
+    ctx: Context<UpdateStakingPool>,
+    pool_id: u8,
+    collection: Pubkey,
+    boost_bps: u16,
+) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+    require_no_timelock(&ctx.accounts.economics)?;
+    require!(boost_bps <= StakingPool::MAX_NFT_BOOST_BPS, EconomicsError::InvalidNftBoostConfig);
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.nft_collection = collection;
+    pool.nft_boost_bps = boost_bps;
+
+    emit!(NftBoostConfiguredEvent {
+        pool_id,
+        collection,
+        boost_bps,
+    });
+    Ok(())
+}
+
+/// Replaces the pool's loyalty tiers; an empty list turns the bonus off.
+/// Positions pick up the change the next time they are re-weighted.
+pub fn set_loyalty_tiers(ctx: Context<UpdateStakingPool>, pool_id: u8, tiers: Vec<LoyaltyTier>) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+    require_no_timelock(&ctx.accounts.economics)?;
+    validate_loyalty_tiers(&tiers)?;
+
+    ctx.accounts.staking_pool.loyalty_tiers = tiers.clone();
+
+    emit!(LoyaltyTiersUpdatedEvent { pool_id, tiers });
+    Ok(())
+}
+
+/// Stops new stakes; claims and withdrawals keep working
+pub fn deactivate_staking_pool(ctx: Context<UpdateStakingPool>, pool_id: u8) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    require!(pool.is_active, EconomicsError::PoolNotActive);
+    pool.is_active = false;
+
+    emit!(StakingPoolDeactivatedEvent { pool_id });
+    Ok(())
+}
+
+pub fn reactivate_staking_pool(ctx: Context<UpdateStakingPool>, pool_id: u8) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::PoolManager,
+    )?;
+
+    let pool = &mut ctx.accounts.staking_pool;
+    require!(!pool.is_active, EconomicsError::PoolAlreadyActive);
+    pool.is_active = true;
+
+    emit!(StakingPoolReactivatedEvent { pool_id });
+    Ok(())
+}
+
+/// Returns the rent of a retired, empty pool to the authority. Every user
+/// stake must have been closed first, so no unclaimed reward is left owed.
+///
+/// Remaining accounts: one `[stream vault, stream authority token account,
+/// stream mint, stream token program]` group per reward stream, in the pool's
+/// stream order. Unspent stream funding is returned to the stream authority
+/// and the vault is closed.
+pub fn close_staking_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CloseStakingPool<'info>>,
+    pool_id: u8,
+) -> Result<()> {
+    validate_emission_schedule(&ctx.accounts.economics, ctx.accounts.emission_schedule.as_ref())?;
+    let pool = &ctx.accounts.staking_pool;
+    require!(!pool.is_active, EconomicsError::PoolStillActive);
+    require!(
+        pool.total_staked == 0
+            && pool.active_stakers == 0
+            && pool.total_boosted == 0
+            && pool.total_unbonding == 0,
+        EconomicsError::PoolNotEmpty
+    );
+    require!(pool.open_positions == 0, EconomicsError::PoolHasOpenPositions);
+    require!(
+        ctx.remaining_accounts.len() == pool.reward_streams.len() * 4,
+        EconomicsError::InvalidRewardStreamAccounts
+    );
+
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+    for (stream, stream_accounts) in pool.reward_streams.iter().zip(ctx.remaining_accounts.chunks(4)) {
+        let (stream_vault_info, recipient_info, stream_mint_info, stream_token_program) = (
+            &stream_accounts[0],
+            &stream_accounts[1],
+            &stream_accounts[2],
+            &stream_accounts[3],
+        );
+        require_keys_eq!(stream_vault_info.key(), stream.vault, EconomicsError::InvalidRewardStreamAccounts);
+        require_keys_eq!(stream_mint_info.key(), stream.mint, EconomicsError::InvalidRewardStreamAccounts);
+        require_keys_eq!(*stream_mint_info.owner, stream_token_program.key(), EconomicsError::InvalidRewardStreamAccounts);
+        let stream_mint = InterfaceAccount::<Mint>::try_from(stream_mint_info)?;
+        let stream_vault = InterfaceAccount::<TokenAccount>::try_from(stream_vault_info)?;
+        let recipient = InterfaceAccount::<TokenAccount>::try_from(recipient_info)?;
+        require_keys_eq!(recipient.mint, stream.mint, EconomicsError::InvalidRewardStreamAccounts);
+        require_keys_eq!(recipient.owner, stream.authority, EconomicsError::InvalidRewardStreamAccounts);
+
+        if stream_vault.amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: stream_vault_info.clone(),
+                mint: stream_mint_info.clone(),
+                to: recipient_info.clone(),
+                authority: ctx.accounts.economics.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(stream_token_program.clone(), cpi_accounts, signer_seeds);
+            token_interface::transfer_checked(cpi_ctx, stream_vault.amount, stream_mint.decimals)?;
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: stream_vault_info.clone(),
+            destination: ctx.accounts.authority.to_account_info(),
+            authority: ctx.accounts.economics.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(stream_token_program.clone(), cpi_accounts, signer_seeds);
+        token_interface::close_account(cpi_ctx)?;
+    }
+
+    // Campaign funding the pool never emitted goes back to the shared pool
+    let campaign_reserved = pool.campaign_reserved;
+    let economics = &mut ctx.accounts.economics;
+    economics.set_pool(pool_id, false);
+    economics.campaign_reserved = economics.campaign_reserved
+        .checked_sub(campaign_reserved)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.available_rewards = economics.available_rewards
+        .checked_add(campaign_reserved)
+        .ok_or(EconomicsError::MathOverflow)?;
+    refresh_rewards_funded_until(
+        economics,
+        ctx.accounts.emission_schedule.as_deref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    emit!(StakingPoolClosedEvent { pool_id });
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{AdminControl, RegisterReferrer, ClaimReferralRewards};
+use crate::events::{ReferralShareUpdatedEvent, ReferrerRegisteredEvent, ReferralRewardsClaimedEvent};
+use crate::errors::EconomicsError;
+use crate::utils::release_committed_rewards;
+
+/// Sets the share of a referred staker's governance fee paid to the referrer
+pub fn set_referral_share(ctx: Context<AdminControl>, share_bps: u16) -> Result<()> {
+    require!(share_bps <= 10000, EconomicsError::InvalidFeeRate);
+    ctx.accounts.economics.referral_share_bps = share_bps;
+
+    emit!(ReferralShareUpdatedEvent { share_bps });
+    Ok(())
+}
+
+/// Opens the account referral rewards accrue to; stakers can only name a
+/// registered referrer
+pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referrer_account = &mut ctx.accounts.referrer_account;
+    referrer_account.referrer = ctx.accounts.referrer.key();
+    referrer_account.bump = ctx.bumps.referrer_account;
+
+    emit!(ReferrerRegisteredEvent {
+        referrer: referrer_account.referrer,
+    });
+    Ok(())
+}
+
+pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+
+    let amount = ctx.accounts.referrer_account.pending_rewards;
+    require!(amount > 0, EconomicsError::NoRewardsToClaim);
+    ctx.accounts.referrer_account.pending_rewards = 0;
+    release_committed_rewards(&mut ctx.accounts.economics, amount);
+
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.reward_vault.to_account_info(),
+        mint: ctx.accounts.reward_mint.to_account_info(),
+        to: ctx.accounts.referrer_reward_account.to_account_info(),
+        authority: ctx.accounts.economics.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.reward_mint.decimals)?;
+
+    emit!(ReferralRewardsClaimedEvent {
+        referrer: ctx.accounts.referrer.key(),
+        amount,
+    });
+    Ok(())
+}
@@ -1,28 +1,50 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
-use crate::context::ClaimRewards;
-use crate::events::RewardClaimEvent;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TransferChecked};
+use crate::context::{ClaimRewards, ClaimAll};
+use crate::state::{StakingPool, UserStake};
+use crate::events::{RewardClaimEvent, StreamRewardPaid, ClaimAllEvent, PoolRewardClaimed};
 use crate::errors::EconomicsError;
-use crate::utils::{update_reward_accumulation, calculate_pending_rewards, calculate_reward_debt};
+use crate::utils::{
+    update_reward_accumulation, settle_user_rewards, sync_reward_debts, refresh_boosted_balance,
+    release_committed_rewards, validate_fee_distribution, distribute_governance_fee, FeeAccounts, credit_referral,
+};
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+/// Claims the primary reward and every additional reward stream of the pool.
+///
+/// Remaining accounts: one `[stream vault, user token account, stream mint,
+/// stream token program]` group per reward stream, in the pool's stream order,
+/// followed by the token accounts of the fee split's external recipients.
+pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
     require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
     require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+    validate_fee_distribution(&ctx.accounts.economics, ctx.accounts.fee_distribution.as_ref())?;
+
+    let stream_account_count = ctx.accounts.staking_pool.reward_streams.len() * 4;
+    require!(
+        ctx.remaining_accounts.len() >= stream_account_count,
+        EconomicsError::InvalidRewardStreamAccounts
+    );
+    let (stream_accounts, fee_recipients) = ctx.remaining_accounts.split_at(stream_account_count);
 
     let economics = &mut ctx.accounts.economics;
     let pool = &mut ctx.accounts.staking_pool;
     let user_stake = &mut ctx.accounts.user_stake;
 
     // Update reward accumulation
-    update_reward_accumulation(economics, pool)?;
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
 
     // Calculate total claimable rewards
-    let pending_rewards = calculate_pending_rewards(user_stake, pool)?;
-    let total_rewards = user_stake.pending_rewards
-        .checked_add(pending_rewards)
-        .ok_or(EconomicsError::MathOverflow)?;
+    settle_user_rewards(user_stake, pool)?;
+    let total_rewards = user_stake.pending_rewards;
+    let stream_amounts: Vec<u64> = user_stake.stream_rewards
+        .iter()
+        .map(|stream_reward| stream_reward.pending_rewards)
+        .collect();
 
-    require!(total_rewards > 0, EconomicsError::NoRewardsToClaim);
+    require!(
+        total_rewards > 0 || stream_amounts.iter().any(|amount| *amount > 0),
+        EconomicsError::NoRewardsToClaim
+    );
 
     // Calculate governance fee
     let governance_fee = total_rewards
@@ -35,58 +57,299 @@ pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
         .checked_sub(governance_fee)
         .ok_or(EconomicsError::MathOverflow)?;
 
-    // Reset user's reward state
+    // Reset user's reward state, applying any boost decay since the last touch
+    let now = Clock::get()?.unix_timestamp;
+    refresh_boosted_balance(user_stake, pool, now)?;
     user_stake.pending_rewards = 0;
-    user_stake.reward_debt = calculate_reward_debt(user_stake.total_staked, pool)?;
+    for stream_reward in user_stake.stream_rewards.iter_mut() {
+        stream_reward.pending_rewards = 0;
+    }
+    sync_reward_debts(user_stake, pool)?;
     user_stake.total_rewards_claimed = user_stake.total_rewards_claimed
         .checked_add(user_reward)
         .ok_or(EconomicsError::MathOverflow)?;
-    user_stake.last_claim_time = Clock::get()?.unix_timestamp;
+    user_stake.last_claim_time = now;
 
     // Update global statistics
     economics.total_rewards_distributed = economics.total_rewards_distributed
         .checked_add(total_rewards)
         .ok_or(EconomicsError::MathOverflow)?;
 
+    // The referrer's cut comes out of the governance fee and stays committed
+    // in the vault until the referrer claims it
+    let referral_reward = credit_referral(
+        economics,
+        &ctx.accounts.referral,
+        ctx.accounts.referrer_account.as_mut(),
+        ctx.accounts.user.key(),
+        governance_fee,
+    )?;
+    release_committed_rewards(
+        economics,
+        total_rewards.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+    );
+
     // Create signer seeds for PDA
     let seeds = &[b"economics".as_ref(), &[economics.bump]];
     let signer_seeds = &[&seeds[..]];
 
-    // Transfer governance fee to treasury
-    if governance_fee > 0 {
-        let treasury_cpi_accounts = Transfer {
+    // Pay the governance fee to the treasury or across the fee split
+    distribute_governance_fee(
+        governance_fee.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+        ctx.accounts.fee_distribution.as_deref(),
+        FeeAccounts {
+            vault: &ctx.accounts.reward_vault.to_account_info(),
+            treasury_vault: &ctx.accounts.treasury_vault.to_account_info(),
+            mint: &ctx.accounts.reward_mint,
+            authority: &ctx.accounts.economics.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            recipients: fee_recipients,
+        },
+        signer_seeds,
+    )?;
+
+    // Transfer user rewards
+    if user_reward > 0 {
+        let user_cpi_accounts = TransferChecked {
             from: ctx.accounts.reward_vault.to_account_info(),
-            to: ctx.accounts.treasury_vault.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_reward_account.to_account_info(),
             authority: ctx.accounts.economics.to_account_info(),
         };
-        let treasury_cpi_ctx = CpiContext::new_with_signer(
+        let user_cpi_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
-            treasury_cpi_accounts,
+            user_cpi_accounts,
             signer_seeds,
         );
-        token::transfer(treasury_cpi_ctx, governance_fee)?;
+        token_interface::transfer_checked(user_cpi_ctx, user_reward, ctx.accounts.reward_mint.decimals)?;
     }
 
-    // Transfer user rewards
-    let user_cpi_accounts = Transfer {
-        from: ctx.accounts.reward_vault.to_account_info(),
-        to: ctx.accounts.user_reward_account.to_account_info(),
-        authority: ctx.accounts.economics.to_account_info(),
-    };
-    let user_cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        user_cpi_accounts,
-        signer_seeds,
-    );
-    token::transfer(user_cpi_ctx, user_reward)?;
+    // Pay every co-incentive stream from its own vault (no governance fee)
+    let reward_streams = &ctx.accounts.staking_pool.reward_streams;
+    let mut stream_rewards = Vec::with_capacity(reward_streams.len());
+    for ((stream, amount), stream_accounts) in reward_streams
+        .iter()
+        .zip(stream_amounts)
+        .zip(stream_accounts.chunks(4))
+    {
+        let (stream_vault, user_stream_account, stream_mint_info, stream_token_program) = (
+            &stream_accounts[0],
+            &stream_accounts[1],
+            &stream_accounts[2],
+            &stream_accounts[3],
+        );
+        require_keys_eq!(stream_vault.key(), stream.vault, EconomicsError::InvalidRewardStreamAccounts);
+        require_keys_eq!(stream_mint_info.key(), stream.mint, EconomicsError::InvalidRewardStreamAccounts);
+        // Streams may use either token program, so the program must own the mint
+        require_keys_eq!(*stream_mint_info.owner, stream_token_program.key(), EconomicsError::InvalidRewardStreamAccounts);
+        let stream_mint = InterfaceAccount::<Mint>::try_from(stream_mint_info)?;
+        let user_token_account = InterfaceAccount::<TokenAccount>::try_from(user_stream_account)?;
+        require_keys_eq!(user_token_account.mint, stream.mint, EconomicsError::InvalidRewardStreamAccounts);
+        require_keys_eq!(user_token_account.owner, ctx.accounts.user.key(), EconomicsError::InvalidRewardStreamAccounts);
+
+        if amount > 0 {
+            let stream_cpi_accounts = TransferChecked {
+                from: stream_vault.clone(),
+                mint: stream_mint_info.clone(),
+                to: user_stream_account.clone(),
+                authority: ctx.accounts.economics.to_account_info(),
+            };
+            let stream_cpi_ctx = CpiContext::new_with_signer(
+                stream_token_program.clone(),
+                stream_cpi_accounts,
+                signer_seeds,
+            );
+            token_interface::transfer_checked(stream_cpi_ctx, amount, stream_mint.decimals)?;
+        }
+
+        stream_rewards.push(StreamRewardPaid {
+            mint: stream.mint,
+            amount,
+        });
+    }
 
     emit!(RewardClaimEvent {
         user: ctx.accounts.user.key(),
-        pool_id: user_stake.pool_id,
+        pool_id: ctx.accounts.user_stake.pool_id,
+        gross_reward: total_rewards,
+        governance_fee,
+        net_reward: user_reward,
+        stream_rewards,
+    });
+
+    Ok(())
+}
+
+/// Claims the primary reward of several positions with one fee transfer and
+/// one payout.
+///
+/// Remaining accounts: one writable `[staking pool, user stake]` pair per
+/// position, at most `UserStake::MAX_CLAIM_ALL_POSITIONS`, followed by the token
+/// accounts of the fee split's external recipients. Reward streams are settled
+/// but stay pending until the pool's own `claim_rewards`.
+pub fn claim_all<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimAll<'info>>) -> Result<()> {
+    require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+
+    validate_fee_distribution(&ctx.accounts.economics, ctx.accounts.fee_distribution.as_ref())?;
+    let fee_recipient_count = ctx.accounts.fee_distribution
+        .as_ref()
+        .map_or(0, |distribution| distribution.token_account_count());
+    require!(
+        ctx.remaining_accounts.len() >= fee_recipient_count,
+        EconomicsError::InvalidClaimAllAccounts
+    );
+    let (position_accounts, fee_recipients) = ctx.remaining_accounts
+        .split_at(ctx.remaining_accounts.len() - fee_recipient_count);
+    require!(
+        !position_accounts.is_empty()
+            && position_accounts.chunks_exact(2).remainder().is_empty()
+            && position_accounts.len() / 2 <= UserStake::MAX_CLAIM_ALL_POSITIONS,
+        EconomicsError::InvalidClaimAllAccounts
+    );
+
+    let economics = &mut ctx.accounts.economics;
+    let user = ctx.accounts.user.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut total_rewards: u64 = 0;
+    let mut governance_fee: u64 = 0;
+    let mut pool_rewards: Vec<PoolRewardClaimed> = Vec::with_capacity(position_accounts.len() / 2);
+    for pair in position_accounts.chunks_exact(2) {
+        let (pool_info, user_stake_info) = (&pair[0], &pair[1]);
+        require!(
+            pool_info.is_writable && user_stake_info.is_writable,
+            EconomicsError::InvalidClaimAllAccounts
+        );
+
+        // Owner and discriminator are checked on load, the addresses against the stored bumps
+        let mut pool = Account::<StakingPool>::try_from(pool_info)?;
+        let mut user_stake = Account::<UserStake>::try_from(user_stake_info)?;
+        require_keys_eq!(user_stake.user, user, EconomicsError::InvalidClaimAllAccounts);
+        require!(user_stake.pool_id == pool.pool_id, EconomicsError::InvalidClaimAllAccounts);
+        let pool_address = Pubkey::create_program_address(
+            &[b"pool", &[pool.pool_id], &[pool.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(EconomicsError::InvalidClaimAllAccounts))?;
+        require_keys_eq!(pool_address, pool_info.key(), EconomicsError::InvalidClaimAllAccounts);
+        let user_stake_address = Pubkey::create_program_address(
+            &[b"user_stake", user.as_ref(), &[user_stake.pool_id], &[user_stake.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(EconomicsError::InvalidClaimAllAccounts))?;
+        require_keys_eq!(user_stake_address, user_stake_info.key(), EconomicsError::InvalidClaimAllAccounts);
+
+        // A repeated pair would be loaded twice and paid out twice
+        require!(
+            pool_rewards.iter().all(|claimed| claimed.pool_id != pool.pool_id),
+            EconomicsError::InvalidClaimAllAccounts
+        );
+
+        update_reward_accumulation(economics, &mut pool, ctx.accounts.emission_schedule.as_ref())?;
+        settle_user_rewards(&mut user_stake, &pool)?;
+
+        // Fee per position so a batch pays exactly what separate claims would
+        let position_reward = user_stake.pending_rewards;
+        let position_fee = position_reward
+            .checked_mul(economics.governance_fee_bps as u64)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_div(10000)
+            .ok_or(EconomicsError::MathOverflow)?;
+        let position_net = position_reward
+            .checked_sub(position_fee)
+            .ok_or(EconomicsError::MathOverflow)?;
+
+        refresh_boosted_balance(&mut user_stake, &mut pool, now)?;
+        user_stake.pending_rewards = 0;
+        sync_reward_debts(&mut user_stake, &pool)?;
+        if position_reward > 0 {
+            user_stake.total_rewards_claimed = user_stake.total_rewards_claimed
+                .checked_add(position_net)
+                .ok_or(EconomicsError::MathOverflow)?;
+            user_stake.last_claim_time = now;
+        }
+
+        total_rewards = total_rewards
+            .checked_add(position_reward)
+            .ok_or(EconomicsError::MathOverflow)?;
+        governance_fee = governance_fee
+            .checked_add(position_fee)
+            .ok_or(EconomicsError::MathOverflow)?;
+        pool_rewards.push(PoolRewardClaimed {
+            pool_id: pool.pool_id,
+            gross_reward: position_reward,
+        });
+
+        pool.exit(&crate::ID)?;
+        user_stake.exit(&crate::ID)?;
+    }
+
+    require!(total_rewards > 0, EconomicsError::NoRewardsToClaim);
+
+    let user_reward = total_rewards
+        .checked_sub(governance_fee)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // Update global statistics
+    economics.total_rewards_distributed = economics.total_rewards_distributed
+        .checked_add(total_rewards)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // The referrer's cut comes out of the governance fee and stays committed
+    // in the vault until the referrer claims it
+    let referral_reward = credit_referral(
+        economics,
+        &ctx.accounts.referral,
+        ctx.accounts.referrer_account.as_mut(),
+        user,
+        governance_fee,
+    )?;
+    release_committed_rewards(
+        economics,
+        total_rewards.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+    );
+
+    // Create signer seeds for PDA
+    let seeds = &[b"economics".as_ref(), &[economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // Pay the aggregated governance fee to the treasury or across the fee split
+    distribute_governance_fee(
+        governance_fee.checked_sub(referral_reward).ok_or(EconomicsError::MathOverflow)?,
+        ctx.accounts.fee_distribution.as_deref(),
+        FeeAccounts {
+            vault: &ctx.accounts.reward_vault.to_account_info(),
+            treasury_vault: &ctx.accounts.treasury_vault.to_account_info(),
+            mint: &ctx.accounts.reward_mint,
+            authority: &ctx.accounts.economics.to_account_info(),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            recipients: fee_recipients,
+        },
+        signer_seeds,
+    )?;
+
+    // Transfer the aggregated user rewards
+    if user_reward > 0 {
+        let user_cpi_accounts = TransferChecked {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            mint: ctx.accounts.reward_mint.to_account_info(),
+            to: ctx.accounts.user_reward_account.to_account_info(),
+            authority: ctx.accounts.economics.to_account_info(),
+        };
+        let user_cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            user_cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(user_cpi_ctx, user_reward, ctx.accounts.reward_mint.decimals)?;
+    }
+
+    emit!(ClaimAllEvent {
+        user,
         gross_reward: total_rewards,
         governance_fee,
         net_reward: user_reward,
+        pool_rewards,
     });
 
     Ok(())
-}
\ No newline at end of file
+}
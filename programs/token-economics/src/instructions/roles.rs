@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::context::{GrantRole, RevokeRole};
+use crate::events::{RoleGrantedEvent, RoleRevokedEvent};
+use crate::state::Role;
+
+pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, role: Role) -> Result<()> {
+    let role_assignment = &mut ctx.accounts.role_assignment;
+
+    // First grant for this member creates the assignment
+    if role_assignment.member == Pubkey::default() {
+        role_assignment.economics = ctx.accounts.economics.key();
+        role_assignment.member = member;
+        role_assignment.bump = ctx.bumps.role_assignment;
+    }
+    role_assignment.roles |= role.mask();
+
+    emit!(RoleGrantedEvent {
+        member,
+        role,
+        roles: role_assignment.roles,
+    });
+    Ok(())
+}
+
+/// Removes a role; the assignment is closed once the member holds none
+pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey, role: Role) -> Result<()> {
+    let role_assignment = &mut ctx.accounts.role_assignment;
+    role_assignment.roles &= !role.mask();
+    let roles = role_assignment.roles;
+
+    if roles == 0 {
+        role_assignment.close(ctx.accounts.authority.to_account_info())?;
+    }
+
+    emit!(RoleRevokedEvent {
+        member,
+        role,
+        roles,
+    });
+    Ok(())
+}
@@ -1,16 +1,48 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
-use crate::context::{StakeTokens, UnstakeTokens, EmergencyUnstake};
-use crate::events::{StakeEvent, UnstakeEvent, EmergencyUnstakeEvent};
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{StakeTokens, UnstakeTokens, UnstakeEarly, EmergencyUnstake, CloseUserStake};
+use crate::events::{
+    StakeEvent, UnstakeEvent, EarlyExitPenaltyEvent, EmergencyUnstakeEvent, UserStakeClosedEvent, ReferralBoundEvent,
+};
 use crate::errors::EconomicsError;
-use crate::utils::{update_reward_accumulation, calculate_pending_rewards, calculate_reward_debt};
+use crate::utils::{
+    update_reward_accumulation, settle_user_rewards, sync_reward_debts,
+    add_stake_lot, reactivate_position, refresh_boosted_balance, withdraw_stake,
+    forfeit_committed_rewards, forfeit_campaign_rewards, record_stake_checkpoint, update_nft_boost,
+};
+use crate::state::{UserStake, PenaltyDestination};
 
 pub fn stake_tokens(
     ctx: Context<StakeTokens>,
     pool_id: u8,
     amount: u64,
+    lock_duration: i64,
 ) -> Result<()> {
     require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
+    require!(
+        lock_duration == 0
+            || (UserStake::MIN_LOCK_DURATION..=UserStake::MAX_LOCK_DURATION).contains(&lock_duration),
+        EconomicsError::InvalidLockDuration
+    );
+
+    // Transfer tokens from user to vault
+    let vault_balance_before = ctx.accounts.stake_vault.amount;
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.user_token_account.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.stake_vault.to_account_info(),
+        authority: ctx.accounts.user.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+    // Credit only what reached the vault; Token-2022 transfer fees are withheld
+    // from the destination
+    ctx.accounts.stake_vault.reload()?;
+    let amount = ctx.accounts.stake_vault.amount
+        .checked_sub(vault_balance_before)
+        .ok_or(EconomicsError::MathOverflow)?;
     require!(amount >= ctx.accounts.economics.min_stake_amount, EconomicsError::BelowMinimumStake);
     
     let economics = &mut ctx.accounts.economics;
@@ -28,32 +60,44 @@ pub fn stake_tokens(
     );
 
     // Update global reward accumulation
-    update_reward_accumulation(economics, pool)?;
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
 
     // Calculate and store pending rewards before updating stake
-    let pending_rewards = calculate_pending_rewards(user_stake, pool)?;
-    user_stake.pending_rewards = user_stake.pending_rewards
-        .checked_add(pending_rewards)
-        .ok_or(EconomicsError::MathOverflow)?;
+    settle_user_rewards(user_stake, pool)?;
 
-    // Initialize user stake if first time
-    if user_stake.total_staked == 0 {
+    // Initialize the account only when `init_if_needed` just created it; an
+    // existing position keeps its identity, bump and reward history
+    if user_stake.user == Pubkey::default() {
         user_stake.user = ctx.accounts.user.key();
         user_stake.pool_id = pool_id;
-        user_stake.first_stake_time = clock.unix_timestamp;
         user_stake.bump = ctx.bumps.user_stake;
-        pool.active_stakers = pool.active_stakers.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
-        economics.active_stakers = economics.active_stakers.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+        pool.open_positions = pool.open_positions.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+    } else {
+        require_keys_eq!(user_stake.user, ctx.accounts.user.key(), EconomicsError::Unauthorized);
+        require!(user_stake.pool_id == pool_id, EconomicsError::Unauthorized);
     }
 
+    reactivate_position(economics, pool, user_stake, clock.unix_timestamp)?;
+
+    // Record the deposit as its own lot so it does not re-lock earlier deposits.
+    // A voluntary lock longer than the pool minimum earns a decaying boost.
+    let unlock_time = clock.unix_timestamp
+        .checked_add(pool.min_stake_period.max(lock_duration))
+        .ok_or(EconomicsError::MathOverflow)?;
+    let lot_id = add_stake_lot(user_stake, amount, clock.unix_timestamp, unlock_time)?;
+
     // Update stake amounts
     user_stake.total_staked = user_stake.total_staked
         .checked_add(amount)
         .ok_or(EconomicsError::MathOverflow)?;
     user_stake.last_stake_time = clock.unix_timestamp;
+    if let Some(nft_token_account) = ctx.accounts.nft_token_account.as_ref() {
+        update_nft_boost(pool, user_stake, nft_token_account, ctx.accounts.nft_metadata.as_deref())?;
+    }
+    refresh_boosted_balance(user_stake, pool, clock.unix_timestamp)?;
     
     // Calculate new reward debt
-    user_stake.reward_debt = calculate_reward_debt(user_stake.total_staked, pool)?;
+    sync_reward_debts(user_stake, pool)?;
 
     // Update pool and global totals
     pool.total_staked = pool.total_staked
@@ -63,15 +107,32 @@ pub fn stake_tokens(
         .checked_add(amount)
         .ok_or(EconomicsError::MathOverflow)?;
 
-    // Transfer tokens from user to vault
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.user_token_account.to_account_info(),
-        to: ctx.accounts.stake_vault.to_account_info(),
-        authority: ctx.accounts.user.to_account_info(),
-    };
-    let cpi_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-    token::transfer(cpi_ctx, amount)?;
+    record_stake_checkpoint(&mut ctx.accounts.checkpoints, user_stake, ctx.bumps.checkpoints, clock.unix_timestamp);
+
+    // Bind the staker to the first referrer they stake through
+    match (ctx.accounts.referrer_account.as_mut(), ctx.accounts.referral.as_mut()) {
+        (Some(referrer_account), Some(referral)) => {
+            let user = ctx.accounts.user.key();
+            require_keys_neq!(referrer_account.referrer, user, EconomicsError::SelfReferral);
+            if referral.user == Pubkey::default() {
+                referral.user = user;
+                referral.referrer = referrer_account.referrer;
+                referral.bump = ctx.bumps.referral.ok_or(EconomicsError::InvalidReferralAccounts)?;
+                referrer_account.referee_count = referrer_account.referee_count
+                    .checked_add(1)
+                    .ok_or(EconomicsError::MathOverflow)?;
+
+                emit!(ReferralBoundEvent {
+                    user,
+                    referrer: referral.referrer,
+                });
+            } else {
+                require_keys_eq!(referral.referrer, referrer_account.referrer, EconomicsError::ReferrerAlreadySet);
+            }
+        }
+        (None, None) => {}
+        _ => return err!(EconomicsError::InvalidReferralAccounts),
+    }
 
     emit!(StakeEvent {
         user: ctx.accounts.user.key(),
@@ -79,6 +140,9 @@ pub fn stake_tokens(
         amount,
         total_user_stake: user_stake.total_staked,
         total_pool_stake: pool.total_staked,
+        lot_id,
+        unlock_time,
+        boosted_balance: user_stake.boosted_balance,
     });
 
     Ok(())
@@ -86,62 +150,31 @@ pub fn stake_tokens(
 
 pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
     require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+    require!(ctx.accounts.staking_pool.unbonding_period == 0, EconomicsError::UnbondingRequired);
 
     let economics = &mut ctx.accounts.economics;
     let pool = &mut ctx.accounts.staking_pool;
     let user_stake = &mut ctx.accounts.user_stake;
     let clock = Clock::get()?;
 
-    require!(user_stake.total_staked > 0, EconomicsError::NoStakeToUnstake);
-
-    // Check minimum staking period
-    let stake_duration = clock.unix_timestamp - user_stake.last_stake_time;
-    require!(stake_duration >= pool.min_stake_period, EconomicsError::MinimumStakePeriodNotMet);
-
-    // Determine actual unstake amount
-    let unstake_amount = if amount == 0 { 
-        user_stake.total_staked 
-    } else { 
-        amount 
-    };
-    
-    require!(unstake_amount <= user_stake.total_staked, EconomicsError::InsufficientStake);
-
-    // Update reward accumulation and claim pending rewards
-    update_reward_accumulation(economics, pool)?;
-    let pending_rewards = calculate_pending_rewards(user_stake, pool)?;
-    user_stake.pending_rewards = user_stake.pending_rewards
-        .checked_add(pending_rewards)
-        .ok_or(EconomicsError::MathOverflow)?;
-
-    // Update stake amounts
-    user_stake.total_staked = user_stake.total_staked
-        .checked_sub(unstake_amount)
-        .ok_or(EconomicsError::MathOverflow)?;
-
-    // If fully unstaking, handle active staker count
-    if user_stake.total_staked == 0 {
-        pool.active_stakers = pool.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
-        economics.active_stakers = economics.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
-    }
-
-    // Calculate new reward debt
-    user_stake.reward_debt = calculate_reward_debt(user_stake.total_staked, pool)?;
-
-    // Update pool and global totals
-    pool.total_staked = pool.total_staked
-        .checked_sub(unstake_amount)
-        .ok_or(EconomicsError::MathOverflow)?;
-    economics.total_staked = economics.total_staked
-        .checked_sub(unstake_amount)
-        .ok_or(EconomicsError::MathOverflow)?;
+    let withdrawal = withdraw_stake(
+        economics,
+        pool,
+        user_stake,
+        ctx.accounts.emission_schedule.as_ref(),
+        amount,
+        clock.unix_timestamp,
+        false,
+    )?;
+    let unstake_amount = withdrawal.amount;
 
     // Transfer tokens back to user
     let seeds = &[b"economics".as_ref(), &[economics.bump]];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
         to: ctx.accounts.user_token_account.to_account_info(),
         authority: ctx.accounts.economics.to_account_info(),
     };
@@ -150,13 +183,122 @@ pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
         cpi_accounts,
         signer_seeds,
     );
-    token::transfer(cpi_ctx, unstake_amount)?;
+    token_interface::transfer_checked(cpi_ctx, unstake_amount, ctx.accounts.stake_mint.decimals)?;
+
+    record_stake_checkpoint(&mut ctx.accounts.checkpoints, user_stake, ctx.bumps.checkpoints, clock.unix_timestamp);
 
     emit!(UnstakeEvent {
         user: ctx.accounts.user.key(),
         pool_id: user_stake.pool_id,
         amount: unstake_amount,
         remaining_stake: user_stake.total_staked,
+        consumed_lots: withdrawal.consumed_lots,
+    });
+
+    Ok(())
+}
+
+/// Releases stake before its lots unlock, withholding the pool's early-exit
+/// penalty on the locked part (amount 0 = the whole position)
+pub fn unstake_early(ctx: Context<UnstakeEarly>, amount: u64) -> Result<()> {
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+    require!(ctx.accounts.staking_pool.unbonding_period == 0, EconomicsError::UnbondingRequired);
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    let withdrawal = withdraw_stake(
+        economics,
+        pool,
+        user_stake,
+        ctx.accounts.emission_schedule.as_ref(),
+        amount,
+        clock.unix_timestamp,
+        true,
+    )?;
+    let penalty = withdrawal.penalty;
+    let payout = withdrawal.amount
+        .checked_sub(penalty)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // Redistribute through the accumulator while anyone is left to receive it,
+    // otherwise keep the penalty for the treasury
+    let penalty_destination = if pool.penalty_destination == PenaltyDestination::Stakers && pool.total_boosted > 0 {
+        PenaltyDestination::Stakers
+    } else {
+        PenaltyDestination::Treasury
+    };
+    if penalty > 0 && penalty_destination == PenaltyDestination::Stakers {
+        let reward_per_token_delta = (penalty as u128)
+            .checked_mul(1_000_000_000_000)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_div(pool.total_boosted as u128)
+            .ok_or(EconomicsError::MathOverflow)?;
+        pool.accumulated_reward_per_token = pool.accumulated_reward_per_token
+            .checked_add(reward_per_token_delta as u64)
+            .ok_or(EconomicsError::MathOverflow)?;
+        // Funded and owed at once, so it never counts as available
+        economics.committed_rewards = economics.committed_rewards
+            .checked_add(penalty)
+            .ok_or(EconomicsError::MathOverflow)?;
+    }
+
+    let seeds = &[b"economics".as_ref(), &[economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    // Transfer tokens back to user
+    if payout > 0 {
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            mint: ctx.accounts.stake_mint.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.economics.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, payout, ctx.accounts.stake_mint.decimals)?;
+    }
+
+    // Move the penalty out of the stake vault
+    if penalty > 0 {
+        let penalty_account = match penalty_destination {
+            PenaltyDestination::Stakers => ctx.accounts.reward_vault.to_account_info(),
+            PenaltyDestination::Treasury => ctx.accounts.penalty_vault.to_account_info(),
+        };
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            mint: ctx.accounts.stake_mint.to_account_info(),
+            to: penalty_account,
+            authority: ctx.accounts.economics.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        );
+        token_interface::transfer_checked(cpi_ctx, penalty, ctx.accounts.stake_mint.decimals)?;
+
+        emit!(EarlyExitPenaltyEvent {
+            user: ctx.accounts.user.key(),
+            pool_id: user_stake.pool_id,
+            penalty,
+            penalty_destination,
+        });
+    }
+
+    record_stake_checkpoint(&mut ctx.accounts.checkpoints, user_stake, ctx.bumps.checkpoints, clock.unix_timestamp);
+
+    emit!(UnstakeEvent {
+        user: ctx.accounts.user.key(),
+        pool_id: user_stake.pool_id,
+        amount: withdrawal.amount,
+        remaining_stake: user_stake.total_staked,
+        consumed_lots: withdrawal.consumed_lots,
     });
 
     Ok(())
@@ -164,17 +306,44 @@ pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
 
 pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
     let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
     let user_stake = &mut ctx.accounts.user_stake;
 
     require!(economics.emergency_mode, EconomicsError::NotInEmergencyMode);
     require!(user_stake.total_staked > 0, EconomicsError::NoStakeToUnstake);
 
+    // Rewards given up here go back to the funded balances
+    settle_user_rewards(user_stake, pool)?;
+    if pool.campaign_end_time > 0 {
+        forfeit_campaign_rewards(economics, pool, user_stake.pending_rewards)?;
+    } else {
+        forfeit_committed_rewards(economics, user_stake.pending_rewards)?;
+    }
+    for (stream, user_reward) in pool.reward_streams.iter_mut().zip(user_stake.stream_rewards.iter()) {
+        stream.available_rewards = stream.available_rewards
+            .checked_add(user_reward.pending_rewards)
+            .ok_or(EconomicsError::MathOverflow)?;
+    }
+
     let unstake_amount = user_stake.total_staked;
     user_stake.total_staked = 0;
     user_stake.pending_rewards = 0;
     user_stake.reward_debt = 0;
+    user_stake.lots.clear();
+    user_stake.stream_rewards.clear();
+
+    // The position is now empty, so it no longer counts as an active staker
+    pool.active_stakers = pool.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
+    economics.active_stakers = economics.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
 
-    // Update global totals
+    // Update pool and global totals
+    pool.total_staked = pool.total_staked
+        .checked_sub(unstake_amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    pool.total_boosted = pool.total_boosted
+        .checked_sub(user_stake.boosted_balance)
+        .ok_or(EconomicsError::MathOverflow)?;
+    user_stake.boosted_balance = 0;
     economics.total_staked = economics.total_staked
         .checked_sub(unstake_amount)
         .ok_or(EconomicsError::MathOverflow)?;
@@ -183,8 +352,9 @@ pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
     let seeds = &[b"economics".as_ref(), &[economics.bump]];
     let signer_seeds = &[&seeds[..]];
 
-    let cpi_accounts = Transfer {
+    let cpi_accounts = TransferChecked {
         from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
         to: ctx.accounts.user_token_account.to_account_info(),
         authority: ctx.accounts.economics.to_account_info(),
     };
@@ -193,7 +363,14 @@ pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
         cpi_accounts,
         signer_seeds,
     );
-    token::transfer(cpi_ctx, unstake_amount)?;
+    token_interface::transfer_checked(cpi_ctx, unstake_amount, ctx.accounts.stake_mint.decimals)?;
+
+    record_stake_checkpoint(
+        &mut ctx.accounts.checkpoints,
+        &ctx.accounts.user_stake,
+        ctx.bumps.checkpoints,
+        Clock::get()?.unix_timestamp,
+    );
 
     emit!(EmergencyUnstakeEvent {
         user: ctx.accounts.user.key(),
@@ -201,4 +378,30 @@ pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
     });
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Returns the rent of an empty position to its owner
+pub fn close_user_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+    let user_stake = &ctx.accounts.user_stake;
+
+    // With no stake left the position cannot accrue anything further, so the
+    // stored pending amounts are final
+    require!(
+        user_stake.total_staked == 0
+            && user_stake.lots.is_empty()
+            && user_stake.pending_rewards == 0
+            && user_stake.stream_rewards.iter().all(|stream_reward| stream_reward.pending_rewards == 0),
+        EconomicsError::PositionNotEmpty
+    );
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.open_positions = pool.open_positions
+        .checked_sub(1)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    emit!(UserStakeClosedEvent {
+        user: user_stake.user,
+        pool_id: user_stake.pool_id,
+    });
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{AddRewardStream, FundRewardStream, UpdateRewardStream};
+use crate::events::{RewardStreamAddedEvent, RewardStreamFundedEvent, RewardStreamRateUpdatedEvent};
+use crate::errors::EconomicsError;
+use crate::state::{StakingPool, RewardStream};
+use crate::utils::{update_reward_accumulation, validate_mint_extensions};
+
+pub fn add_reward_stream(
+    ctx: Context<AddRewardStream>,
+    pool_id: u8,
+    rate_per_second: u64,
+    stream_authority: Pubkey,
+) -> Result<()> {
+    validate_mint_extensions(&ctx.accounts.stream_mint)?;
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    require!(
+        pool.reward_streams.len() < StakingPool::MAX_REWARD_STREAMS,
+        EconomicsError::TooManyRewardStreams
+    );
+
+    // Bring the pool up to date so the new stream only accrues from now on
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+
+    let stream = RewardStream {
+        mint: ctx.accounts.stream_mint.key(),
+        vault: ctx.accounts.stream_vault.key(),
+        authority: stream_authority,
+        rate_per_second,
+        accumulated_reward_per_token: 0,
+        available_rewards: 0,
+    };
+    pool.reward_streams.push(stream);
+
+    emit!(RewardStreamAddedEvent {
+        pool_id,
+        stream_index: (pool.reward_streams.len() - 1) as u8,
+        mint: stream.mint,
+        vault: stream.vault,
+        authority: stream.authority,
+        rate_per_second,
+    });
+    Ok(())
+}
+
+/// Anyone, typically the partner behind the stream, may top up its vault
+pub fn fund_reward_stream(
+    ctx: Context<FundRewardStream>,
+    pool_id: u8,
+    stream_index: u8,
+    amount: u64,
+) -> Result<()> {
+    let stream = ctx.accounts.staking_pool.reward_streams
+        .get(stream_index as usize)
+        .ok_or(EconomicsError::InvalidRewardStream)?;
+    require_keys_eq!(ctx.accounts.stream_vault.key(), stream.vault, EconomicsError::InvalidRewardStreamAccounts);
+
+    let vault_balance_before = ctx.accounts.stream_vault.amount;
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.funder_token_account.to_account_info(),
+        mint: ctx.accounts.stream_mint.to_account_info(),
+        to: ctx.accounts.stream_vault.to_account_info(),
+        authority: ctx.accounts.funder.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.stream_mint.decimals)?;
+
+    // Report what the vault actually received after any transfer fee
+    ctx.accounts.stream_vault.reload()?;
+    let amount = ctx.accounts.stream_vault.amount.saturating_sub(vault_balance_before);
+
+    // Settle first so time the stream spent unfunded is not paid from the new tokens
+    let pool = &mut ctx.accounts.staking_pool;
+    update_reward_accumulation(&mut ctx.accounts.economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+    let stream = &mut pool.reward_streams[stream_index as usize];
+    stream.available_rewards = stream.available_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    emit!(RewardStreamFundedEvent {
+        pool_id,
+        stream_index,
+        funder: ctx.accounts.funder.key(),
+        amount,
+        available_rewards: stream.available_rewards,
+    });
+    Ok(())
+}
+
+pub fn set_reward_stream_rate(
+    ctx: Context<UpdateRewardStream>,
+    pool_id: u8,
+    stream_index: u8,
+    new_rate: u64,
+) -> Result<()> {
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let stream_authority = pool.reward_streams
+        .get(stream_index as usize)
+        .ok_or(EconomicsError::InvalidRewardStream)?
+        .authority;
+    let signer = ctx.accounts.authority.key();
+    require!(
+        signer == stream_authority || signer == economics.authority,
+        EconomicsError::Unauthorized
+    );
+
+    // Settle accrual at the old rate before switching
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+    pool.reward_streams[stream_index as usize].rate_per_second = new_rate;
+
+    emit!(RewardStreamRateUpdatedEvent {
+        pool_id,
+        stream_index,
+        new_rate,
+    });
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use anchor_lang::prelude::*;
+use crate::context::{QueueParameterChange, ExecuteParameterChange, CancelParameterChange};
+use crate::events::{ParameterChangeQueuedEvent, ParameterChangeExecutedEvent, ParameterChangeCancelledEvent};
+use crate::errors::EconomicsError;
+use crate::state::{TokenEconomics, StakingPool, EmissionSchedule, ParameterChange};
+use crate::utils::{
+    require_role, update_reward_accumulation, validate_penalty_config, settle_pools,
+    validate_emission_epochs, validate_loyalty_tiers,
+};
+
+/// Schedules a parameter change that anyone can execute once
+/// `economics.timelock_delay` has passed
+pub fn queue_parameter_change(ctx: Context<QueueParameterChange>, change: ParameterChange) -> Result<()> {
+    let signer = ctx.accounts.authority.key();
+    match change.required_role() {
+        Some(role) => require_role(&ctx.accounts.economics, &signer, ctx.accounts.role.as_ref(), role)?,
+        None => require_keys_eq!(signer, ctx.accounts.economics.authority, EconomicsError::Unauthorized),
+    }
+    validate_parameter_change(&change)?;
+    if let ParameterChange::PoolEarlyExitPenalty { penalty_bps, penalty_destination, .. } = change {
+        validate_penalty_config(&ctx.accounts.economics, penalty_bps, penalty_destination)?;
+    }
+
+    let economics = &mut ctx.accounts.economics;
+    let change_id = economics.next_change_id;
+    let eta = Clock::get()?.unix_timestamp
+        .checked_add(economics.timelock_delay)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.next_change_id = change_id.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+
+    let pending_change = &mut ctx.accounts.pending_change;
+    pending_change.economics = economics.key();
+    pending_change.change_id = change_id;
+    pending_change.change = change.clone();
+    pending_change.eta = eta;
+    pending_change.proposer = signer;
+    pending_change.bump = ctx.bumps.pending_change;
+
+    emit!(ParameterChangeQueuedEvent {
+        change_id,
+        change,
+        eta,
+        proposer: signer,
+    });
+    Ok(())
+}
+
+/// Remaining accounts: for a `RewardRate` or emission change, every active
+/// staking pool, writable, so time they have not yet settled accrues under
+/// the old emission
+pub fn execute_parameter_change<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteParameterChange<'info>>,
+    change_id: u64,
+) -> Result<()> {
+    let change = ctx.accounts.pending_change.change.clone();
+    require!(
+        Clock::get()?.unix_timestamp >= ctx.accounts.pending_change.eta,
+        EconomicsError::TimelockNotExpired
+    );
+
+    let economics = &mut ctx.accounts.economics;
+    match change.clone() {
+        ParameterChange::RewardRate { new_rate } => {
+            settle_pools(economics, ctx.remaining_accounts, ctx.accounts.emission_schedule.as_ref())?;
+            economics.reward_rate_per_second = new_rate;
+        }
+        ParameterChange::GovernanceFee { fee_bps } => {
+            economics.governance_fee_bps = fee_bps;
+        }
+        ParameterChange::StakeLimits { min_stake_amount, max_stake_amount } => {
+            economics.min_stake_amount = min_stake_amount;
+            economics.max_stake_amount = max_stake_amount;
+        }
+        ParameterChange::TimelockDelay { delay } => {
+            economics.timelock_delay = delay;
+        }
+        ParameterChange::PoolMultiplier { pool_id, reward_multiplier } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            // Settle accrual at the old multiplier before switching
+            update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+            pool.reward_multiplier = reward_multiplier;
+        }
+        ParameterChange::PoolMinStakePeriod { pool_id, min_stake_period } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.min_stake_period = min_stake_period;
+        }
+        ParameterChange::PoolCapacity { pool_id, max_capacity } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.max_capacity = max_capacity;
+        }
+        ParameterChange::PoolUnbondingPeriod { pool_id, unbonding_period } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.unbonding_period = unbonding_period;
+        }
+        ParameterChange::PoolEarlyExitPenalty { pool_id, penalty_mode, penalty_bps, penalty_destination } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.penalty_mode = penalty_mode;
+            pool.penalty_bps = penalty_bps;
+            pool.penalty_destination = penalty_destination;
+        }
+        ParameterChange::EmissionEpochs { epochs } => {
+            let schedule = settle_for_emission_change(
+                economics,
+                &mut ctx.accounts.emission_schedule,
+                ctx.bumps.emission_schedule,
+                ctx.remaining_accounts,
+            )?;
+            schedule.set_epochs(epochs);
+            economics.emission_schedule = schedule.key();
+        }
+        ParameterChange::EmissionDecay { initial_rate, start_time, decay_period, decay_bps } => {
+            let schedule = settle_for_emission_change(
+                economics,
+                &mut ctx.accounts.emission_schedule,
+                ctx.bumps.emission_schedule,
+                ctx.remaining_accounts,
+            )?;
+            schedule.set_decay(initial_rate, start_time, decay_period, decay_bps);
+            economics.emission_schedule = schedule.key();
+        }
+        ParameterChange::ClearEmissionSchedule => {
+            settle_pools(economics, ctx.remaining_accounts, ctx.accounts.emission_schedule.as_ref())?;
+            economics.emission_schedule = Pubkey::default();
+        }
+        ParameterChange::PoolNftBoost { pool_id, collection, boost_bps } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.nft_collection = collection;
+            pool.nft_boost_bps = boost_bps;
+        }
+        ParameterChange::PoolLoyaltyTiers { pool_id, tiers } => {
+            let pool = target_pool(&mut ctx.accounts.staking_pool, pool_id)?;
+            pool.loyalty_tiers = tiers;
+        }
+    }
+
+    emit!(ParameterChangeExecutedEvent { change_id, change });
+    Ok(())
+}
+
+pub fn cancel_parameter_change(ctx: Context<CancelParameterChange>, change_id: u64) -> Result<()> {
+    emit!(ParameterChangeCancelledEvent {
+        change_id,
+        change: ctx.accounts.pending_change.change.clone(),
+    });
+    Ok(())
+}
+
+fn validate_parameter_change(change: &ParameterChange) -> Result<()> {
+    match *change {
+        // Early-exit penalties need the economics account and are checked by the caller
+        ParameterChange::RewardRate { .. }
+        | ParameterChange::PoolCapacity { .. }
+        | ParameterChange::PoolEarlyExitPenalty { .. }
+        | ParameterChange::ClearEmissionSchedule => {}
+        ParameterChange::GovernanceFee { fee_bps } => {
+            require!(fee_bps <= 10000, EconomicsError::InvalidFeeRate);
+        }
+        ParameterChange::StakeLimits { min_stake_amount, max_stake_amount } => {
+            require!(min_stake_amount > 0, EconomicsError::InvalidStakeAmount);
+            require!(max_stake_amount >= min_stake_amount, EconomicsError::InvalidStakeAmount);
+        }
+        ParameterChange::TimelockDelay { delay } => {
+            require!(
+                (0..=TokenEconomics::MAX_TIMELOCK_DELAY).contains(&delay),
+                EconomicsError::InvalidTimelockDelay
+            );
+        }
+        ParameterChange::PoolMultiplier { reward_multiplier, .. } => {
+            require!(reward_multiplier > 0 && reward_multiplier <= 1000, EconomicsError::InvalidMultiplier);
+        }
+        ParameterChange::PoolMinStakePeriod { min_stake_period, .. } => {
+            require!(min_stake_period >= 0, EconomicsError::InvalidStakePeriod);
+        }
+        ParameterChange::PoolUnbondingPeriod { unbonding_period, .. } => {
+            require!(
+                (0..=StakingPool::MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+                EconomicsError::InvalidStakePeriod
+            );
+        }
+        ParameterChange::EmissionEpochs { ref epochs } => {
+            validate_emission_epochs(epochs)?;
+        }
+        ParameterChange::EmissionDecay { decay_period, decay_bps, .. } => {
+            require!(decay_period > 0, EconomicsError::InvalidEmissionSchedule);
+            require!(decay_bps <= 10000, EconomicsError::InvalidEmissionSchedule);
+        }
+        ParameterChange::PoolNftBoost { boost_bps, .. } => {
+            require!(boost_bps <= StakingPool::MAX_NFT_BOOST_BPS, EconomicsError::InvalidNftBoostConfig);
+        }
+        ParameterChange::PoolLoyaltyTiers { ref tiers, .. } => {
+            validate_loyalty_tiers(tiers)?;
+        }
+    }
+    Ok(())
+}
+
+fn target_pool<'a, 'info>(
+    staking_pool: &'a mut Option<Account<'info, StakingPool>>,
+    pool_id: u8,
+) -> Result<&'a mut Account<'info, StakingPool>> {
+    let pool = staking_pool.as_mut().ok_or(EconomicsError::InvalidParameterChangePool)?;
+    require!(pool.pool_id == pool_id, EconomicsError::InvalidParameterChangePool);
+    Ok(pool)
+}
+
+/// Settles the pools under the schedule still in effect and returns the
+/// schedule account for an emission change to rewrite. The account only
+/// counts as the old schedule while it is the active one.
+fn settle_for_emission_change<'a, 'info>(
+    economics: &mut Account<'info, TokenEconomics>,
+    emission_schedule: &'a mut Option<Account<'info, EmissionSchedule>>,
+    bump: Option<u8>,
+    pool_accounts: &'info [AccountInfo<'info>],
+) -> Result<&'a mut Account<'info, EmissionSchedule>> {
+    let schedule = emission_schedule.as_mut().ok_or(EconomicsError::EmissionScheduleRequired)?;
+    let active_schedule = (economics.emission_schedule != Pubkey::default()).then_some(&*schedule);
+    settle_pools(economics, pool_accounts, active_schedule)?;
+
+    if schedule.economics == Pubkey::default() {
+        schedule.economics = economics.key();
+        schedule.bump = bump.ok_or(EconomicsError::EmissionScheduleRequired)?;
+    }
+    Ok(schedule)
+}
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{ConfigureTreasury, WithdrawTreasury, WithdrawPenalties};
+use crate::events::{
+    TreasuryLimitsUpdatedEvent, TreasuryRecipientUpdatedEvent, TreasuryWithdrawEvent, PenaltyWithdrawEvent,
+};
+use crate::errors::EconomicsError;
+use crate::state::{Role, TreasuryConfig};
+use crate::utils::require_role;
+
+/// Sets the per-epoch spending caps; the first call creates the config and
+/// starts the first epoch
+pub fn set_treasury_limits(
+    ctx: Context<ConfigureTreasury>,
+    epoch_duration: i64,
+    epoch_cap: u64,
+    penalty_epoch_cap: u64,
+) -> Result<()> {
+    require!(epoch_duration > 0, EconomicsError::InvalidTreasuryConfig);
+
+    let treasury_config = &mut ctx.accounts.treasury_config;
+    if treasury_config.economics == Pubkey::default() {
+        treasury_config.economics = ctx.accounts.economics.key();
+        treasury_config.current_epoch_start = Clock::get()?.unix_timestamp;
+        treasury_config.bump = ctx.bumps.treasury_config;
+    }
+    treasury_config.epoch_duration = epoch_duration;
+    treasury_config.epoch_cap = epoch_cap;
+    treasury_config.penalty_epoch_cap = penalty_epoch_cap;
+
+    emit!(TreasuryLimitsUpdatedEvent {
+        epoch_duration,
+        epoch_cap,
+        penalty_epoch_cap,
+    });
+    Ok(())
+}
+
+pub fn add_treasury_recipient(ctx: Context<ConfigureTreasury>, recipient: Pubkey) -> Result<()> {
+    let treasury_config = &mut ctx.accounts.treasury_config;
+    require!(treasury_config.economics != Pubkey::default(), EconomicsError::InvalidTreasuryConfig);
+    require!(
+        !treasury_config.allowed_recipients.contains(&recipient)
+            && treasury_config.allowed_recipients.len() < TreasuryConfig::MAX_RECIPIENTS,
+        EconomicsError::InvalidTreasuryConfig
+    );
+    treasury_config.allowed_recipients.push(recipient);
+
+    emit!(TreasuryRecipientUpdatedEvent {
+        recipient,
+        allowed: true,
+    });
+    Ok(())
+}
+
+pub fn remove_treasury_recipient(ctx: Context<ConfigureTreasury>, recipient: Pubkey) -> Result<()> {
+    let treasury_config = &mut ctx.accounts.treasury_config;
+    let index = treasury_config.allowed_recipients
+        .iter()
+        .position(|allowed| *allowed == recipient)
+        .ok_or(EconomicsError::RecipientNotAllowed)?;
+    treasury_config.allowed_recipients.remove(index);
+
+    emit!(TreasuryRecipientUpdatedEvent {
+        recipient,
+        allowed: false,
+    });
+    Ok(())
+}
+
+/// Moves governance fees to an allowlisted account, within the epoch cap
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Treasurer,
+    )?;
+    require!(amount > 0, EconomicsError::InvalidWithdrawalAmount);
+
+    let treasury_config = &mut ctx.accounts.treasury_config;
+    require!(
+        treasury_config.allowed_recipients.contains(&ctx.accounts.recipient.key()),
+        EconomicsError::RecipientNotAllowed
+    );
+
+    treasury_config.roll_epoch(Clock::get()?.unix_timestamp);
+
+    let spent_this_epoch = treasury_config.spent_this_epoch
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    require!(spent_this_epoch <= treasury_config.epoch_cap, EconomicsError::TreasuryCapExceeded);
+    treasury_config.spent_this_epoch = spent_this_epoch;
+
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.treasury_vault.to_account_info(),
+        mint: ctx.accounts.reward_mint.to_account_info(),
+        to: ctx.accounts.recipient.to_account_info(),
+        authority: ctx.accounts.economics.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.reward_mint.decimals)?;
+    ctx.accounts.treasury_vault.reload()?;
+
+    emit!(TreasuryWithdrawEvent {
+        treasurer: ctx.accounts.authority.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        epoch_start: ctx.accounts.treasury_config.current_epoch_start,
+        spent_this_epoch,
+        treasury_balance: ctx.accounts.treasury_vault.amount,
+    });
+    Ok(())
+}
+
+/// Moves early-exit penalties collected in the penalty vault to an
+/// allowlisted account, within the epoch's penalty cap
+pub fn withdraw_penalties(ctx: Context<WithdrawPenalties>, amount: u64) -> Result<()> {
+    require_role(
+        &ctx.accounts.economics,
+        ctx.accounts.authority.key,
+        ctx.accounts.role.as_ref(),
+        Role::Treasurer,
+    )?;
+    require!(amount > 0, EconomicsError::InvalidWithdrawalAmount);
+
+    let treasury_config = &mut ctx.accounts.treasury_config;
+    require!(
+        treasury_config.allowed_recipients.contains(&ctx.accounts.recipient.key()),
+        EconomicsError::RecipientNotAllowed
+    );
+
+    treasury_config.roll_epoch(Clock::get()?.unix_timestamp);
+
+    let penalty_spent_this_epoch = treasury_config.penalty_spent_this_epoch
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    require!(
+        penalty_spent_this_epoch <= treasury_config.penalty_epoch_cap,
+        EconomicsError::TreasuryCapExceeded
+    );
+    treasury_config.penalty_spent_this_epoch = penalty_spent_this_epoch;
+
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.penalty_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.recipient.to_account_info(),
+        authority: ctx.accounts.economics.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+    ctx.accounts.penalty_vault.reload()?;
+
+    emit!(PenaltyWithdrawEvent {
+        treasurer: ctx.accounts.authority.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        epoch_start: ctx.accounts.treasury_config.current_epoch_start,
+        penalty_spent_this_epoch,
+        penalty_vault_balance: ctx.accounts.penalty_vault.amount,
+    });
+    Ok(())
+}
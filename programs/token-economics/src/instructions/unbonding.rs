@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, TransferChecked};
+use crate::context::{RequestUnstake, WithdrawUnbonded, CancelUnbonding};
+use crate::events::{UnbondingRequestedEvent, UnbondedWithdrawnEvent, UnbondingCancelledEvent};
+use crate::errors::EconomicsError;
+use crate::utils::{
+    update_reward_accumulation, settle_user_rewards, sync_reward_debts, record_stake_checkpoint,
+    add_stake_lot, reactivate_position, refresh_boosted_balance, withdraw_stake,
+};
+
+/// Moves stake into an unbonding ticket. The amount stops earning immediately
+/// and can be withdrawn once the pool's unbonding period has passed.
+pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let clock = Clock::get()?;
+
+    let withdrawal = withdraw_stake(
+        economics,
+        pool,
+        user_stake,
+        ctx.accounts.emission_schedule.as_ref(),
+        amount,
+        clock.unix_timestamp,
+        false,
+    )?;
+    let unbonding_amount = withdrawal.amount;
+
+    pool.total_unbonding = pool.total_unbonding
+        .checked_add(unbonding_amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    let ticket_id = pool.next_ticket_id;
+    pool.next_ticket_id = ticket_id.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+    let withdrawable_at = clock.unix_timestamp
+        .checked_add(pool.unbonding_period)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    let ticket = &mut ctx.accounts.unbonding_ticket;
+    ticket.user = ctx.accounts.user.key();
+    ticket.pool_id = pool.pool_id;
+    ticket.ticket_id = ticket_id;
+    ticket.amount = unbonding_amount;
+    ticket.requested_at = clock.unix_timestamp;
+    ticket.withdrawable_at = withdrawable_at;
+    ticket.bump = ctx.bumps.unbonding_ticket;
+
+    record_stake_checkpoint(&mut ctx.accounts.checkpoints, user_stake, ctx.bumps.checkpoints, clock.unix_timestamp);
+
+    emit!(UnbondingRequestedEvent {
+        user: ticket.user,
+        pool_id: ticket.pool_id,
+        ticket_id,
+        amount: unbonding_amount,
+        withdrawable_at,
+        remaining_stake: user_stake.total_staked,
+        consumed_lots: withdrawal.consumed_lots,
+    });
+
+    Ok(())
+}
+
+/// Pays out a ticket whose cooldown has passed; emergency mode skips the cooldown
+pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+    let ticket = &ctx.accounts.unbonding_ticket;
+    require!(
+        ctx.accounts.economics.emergency_mode
+            || Clock::get()?.unix_timestamp >= ticket.withdrawable_at,
+        EconomicsError::UnbondingNotComplete
+    );
+
+    let pool = &mut ctx.accounts.staking_pool;
+    pool.total_unbonding = pool.total_unbonding
+        .checked_sub(ticket.amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // Transfer tokens back to user
+    let seeds = &[b"economics".as_ref(), &[ctx.accounts.economics.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.stake_vault.to_account_info(),
+        mint: ctx.accounts.stake_mint.to_account_info(),
+        to: ctx.accounts.user_token_account.to_account_info(),
+        authority: ctx.accounts.economics.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_accounts,
+        signer_seeds,
+    );
+    token_interface::transfer_checked(cpi_ctx, ticket.amount, ctx.accounts.stake_mint.decimals)?;
+
+    emit!(UnbondedWithdrawnEvent {
+        user: ticket.user,
+        pool_id: ticket.pool_id,
+        ticket_id: ticket.ticket_id,
+        amount: ticket.amount,
+    });
+
+    Ok(())
+}
+
+/// Returns a ticket's tokens to the position as a new, already unlocked lot
+pub fn cancel_unbonding(ctx: Context<CancelUnbonding>) -> Result<()> {
+    require!(!ctx.accounts.economics.is_paused, EconomicsError::SystemPaused);
+    require!(!ctx.accounts.economics.emergency_mode, EconomicsError::EmergencyMode);
+
+    let economics = &mut ctx.accounts.economics;
+    let pool = &mut ctx.accounts.staking_pool;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let ticket = &ctx.accounts.unbonding_ticket;
+    let amount = ticket.amount;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(pool.total_staked + amount <= pool.max_capacity, EconomicsError::PoolCapacityExceeded);
+    require!(
+        user_stake.total_staked + amount <= economics.max_stake_amount,
+        EconomicsError::ExceedsMaximumStake
+    );
+
+    update_reward_accumulation(economics, pool, ctx.accounts.emission_schedule.as_ref())?;
+    settle_user_rewards(user_stake, pool)?;
+
+    reactivate_position(economics, pool, user_stake, now)?;
+
+    // The tokens already served their lock before unbonding started
+    let lot_id = add_stake_lot(user_stake, amount, now, now)?;
+    user_stake.total_staked = user_stake.total_staked
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    refresh_boosted_balance(user_stake, pool, now)?;
+    sync_reward_debts(user_stake, pool)?;
+
+    pool.total_staked = pool.total_staked
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    pool.total_unbonding = pool.total_unbonding
+        .checked_sub(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.total_staked = economics.total_staked
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    record_stake_checkpoint(&mut ctx.accounts.checkpoints, user_stake, ctx.bumps.checkpoints, now);
+
+    emit!(UnbondingCancelledEvent {
+        user: ticket.user,
+        pool_id: ticket.pool_id,
+        ticket_id: ticket.ticket_id,
+        amount,
+        lot_id,
+        total_user_stake: user_stake.total_staked,
+    });
+
+    Ok(())
+}
@@ -45,25 +45,137 @@ pub mod token_economics {
         instructions::create_staking_pool(ctx, pool_id, reward_multiplier, min_stake_period, max_capacity)
     }
 
-    /// Stake tokens into a specific pool
+    /// Change a pool's multiplier, lock period, capacity or unbonding period (admin or pool manager)
+    pub fn update_staking_pool(
+        ctx: Context<UpdateStakingPool>,
+        pool_id: u8,
+        reward_multiplier: Option<u16>,
+        min_stake_period: Option<i64>,
+        max_capacity: Option<u64>,
+        unbonding_period: Option<i64>,
+    ) -> Result<()> {
+        instructions::update_staking_pool(ctx, pool_id, reward_multiplier, min_stake_period, max_capacity, unbonding_period)
+    }
+
+    /// Allow early exit from locked lots against a penalty (admin or pool manager)
+    pub fn set_early_exit_penalty(
+        ctx: Context<UpdateStakingPool>,
+        pool_id: u8,
+        penalty_mode: state::PenaltyMode,
+        penalty_bps: u16,
+        penalty_destination: state::PenaltyDestination,
+    ) -> Result<()> {
+        instructions::set_early_exit_penalty(ctx, pool_id, penalty_mode, penalty_bps, penalty_destination)
+    }
+
+    /// Boost holders of a verified NFT collection in a pool (admin or pool manager)
+    pub fn set_nft_boost(
+        ctx: Context<UpdateStakingPool>,
+        pool_id: u8,
+        collection: Pubkey,
+        boost_bps: u16,
+    ) -> Result<()> {
+        instructions::set_nft_boost(ctx, pool_id, collection, boost_bps)
+    }
+
+    /// Grant a growing bonus to uninterrupted stake, e.g. +10% after 30 days (admin or pool manager)
+    pub fn set_loyalty_tiers(ctx: Context<UpdateStakingPool>, pool_id: u8, tiers: Vec<state::LoyaltyTier>) -> Result<()> {
+        instructions::set_loyalty_tiers(ctx, pool_id, tiers)
+    }
+
+    /// Stop accepting new stakes in a pool (admin or pool manager)
+    pub fn deactivate_staking_pool(ctx: Context<UpdateStakingPool>, pool_id: u8) -> Result<()> {
+        instructions::deactivate_staking_pool(ctx, pool_id)
+    }
+
+    /// Accept new stakes in a deactivated pool again (admin or pool manager)
+    pub fn reactivate_staking_pool(ctx: Context<UpdateStakingPool>, pool_id: u8) -> Result<()> {
+        instructions::reactivate_staking_pool(ctx, pool_id)
+    }
+
+    /// Close a deactivated, empty pool and reclaim its rent (admin only)
+    pub fn close_staking_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CloseStakingPool<'info>>,
+        pool_id: u8,
+    ) -> Result<()> {
+        instructions::close_staking_pool(ctx, pool_id)
+    }
+
+    /// Stake tokens into a specific pool, optionally locking them for a reward boost
     pub fn stake_tokens(
         ctx: Context<StakeTokens>,
         pool_id: u8,
         amount: u64,
+        lock_duration: i64,
     ) -> Result<()> {
-        instructions::stake_tokens(ctx, pool_id, amount)
+        instructions::stake_tokens(ctx, pool_id, amount, lock_duration)
     }
 
-    /// Claim accumulated rewards from staking
-    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    /// Claim accumulated rewards from staking, including every reward stream
+    pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
         instructions::claim_rewards(ctx)
     }
 
+    /// Claim primary rewards from several pools in one transaction
+    pub fn claim_all<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimAll<'info>>) -> Result<()> {
+        instructions::claim_all(ctx)
+    }
+
+    /// Restake net rewards when the stake and reward mints match
+    pub fn compound<'info>(ctx: Context<'_, '_, 'info, 'info, Compound<'info>>) -> Result<()> {
+        instructions::compound(ctx)
+    }
+
+    /// Compound a position that opted into auto-compounding (permissionless)
+    pub fn crank_compound<'info>(ctx: Context<'_, '_, 'info, 'info, Compound<'info>>) -> Result<()> {
+        instructions::crank_compound(ctx)
+    }
+
+    /// Let anyone compound this position's rewards
+    pub fn enable_auto_compound(ctx: Context<EnableAutoCompound>) -> Result<()> {
+        instructions::enable_auto_compound(ctx)
+    }
+
+    /// Stop permissionless compounding and reclaim the marker's rent
+    pub fn disable_auto_compound(ctx: Context<DisableAutoCompound>) -> Result<()> {
+        instructions::disable_auto_compound(ctx)
+    }
+
     /// Unstake tokens from the pool
     pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
         instructions::unstake_tokens(ctx, amount)
     }
 
+    /// Unstake before the lock ends, paying the pool's early-exit penalty
+    pub fn unstake_early(ctx: Context<UnstakeEarly>, amount: u64) -> Result<()> {
+        instructions::unstake_early(ctx, amount)
+    }
+
+    /// Start unbonding stake in a pool with an unbonding period
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake(ctx, amount)
+    }
+
+    /// Withdraw an unbonding ticket once its cooldown has passed
+    pub fn withdraw_unbonded(ctx: Context<WithdrawUnbonded>) -> Result<()> {
+        instructions::withdraw_unbonded(ctx)
+    }
+
+    /// Cancel an unbonding ticket and restake its tokens
+    pub fn cancel_unbonding(ctx: Context<CancelUnbonding>) -> Result<()> {
+        instructions::cancel_unbonding(ctx)
+    }
+
+    /// Close an empty position and reclaim its rent
+    pub fn close_user_stake(ctx: Context<CloseUserStake>) -> Result<()> {
+        instructions::close_user_stake(ctx)
+    }
+
+    /// Re-weight a position's lock boost as it decays (permissionless)
+    pub fn refresh_boost(ctx: Context<RefreshBoost>) -> Result<()> {
+        instructions::refresh_boost(ctx)
+    }
+
     /// Emergency unstake (admin only)
     pub fn emergency_unstake(ctx: Context<EmergencyUnstake>) -> Result<()> {
         instructions::emergency_unstake(ctx)
@@ -71,23 +183,260 @@ pub mod token_economics {
 
     /// Admin functions for system management
 
-    /// Pause/unpause the system
-    pub fn set_pause_state(ctx: Context<AdminControl>, is_paused: bool) -> Result<()> {
+    /// Pause/unpause the system (admin or pauser)
+    pub fn set_pause_state(ctx: Context<RoleControl>, is_paused: bool) -> Result<()> {
         instructions::set_pause_state(ctx, is_paused)
     }
 
-    /// Enable/disable emergency mode
-    pub fn set_emergency_mode(ctx: Context<AdminControl>, emergency_mode: bool) -> Result<()> {
+    /// Enable/disable emergency mode (admin or pauser)
+    pub fn set_emergency_mode(ctx: Context<RoleControl>, emergency_mode: bool) -> Result<()> {
         instructions::set_emergency_mode(ctx, emergency_mode)
     }
 
-    /// Update reward rate (admin only)
-    pub fn update_reward_rate(ctx: Context<AdminControl>, new_rate: u64) -> Result<()> {
+    /// Update reward rate (admin or rate manager)
+    pub fn update_reward_rate<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateRewardRate<'info>>,
+        new_rate: u64,
+    ) -> Result<()> {
         instructions::update_reward_rate(ctx, new_rate)
     }
 
-    /// Fund the reward vault with tokens (admin only)
+    /// Grant an operational role to a member (admin only)
+    pub fn grant_role(ctx: Context<GrantRole>, member: Pubkey, role: state::Role) -> Result<()> {
+        instructions::grant_role(ctx, member, role)
+    }
+
+    /// Revoke an operational role from a member (admin only)
+    pub fn revoke_role(ctx: Context<RevokeRole>, member: Pubkey, role: state::Role) -> Result<()> {
+        instructions::revoke_role(ctx, member, role)
+    }
+
+    /// Schedule a parameter change behind the timelock (admin or matching role)
+    pub fn queue_parameter_change(ctx: Context<QueueParameterChange>, change: state::ParameterChange) -> Result<()> {
+        instructions::queue_parameter_change(ctx, change)
+    }
+
+    /// Apply a queued parameter change once its delay has passed (permissionless)
+    pub fn execute_parameter_change<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteParameterChange<'info>>,
+        change_id: u64,
+    ) -> Result<()> {
+        instructions::execute_parameter_change(ctx, change_id)
+    }
+
+    /// Drop a queued parameter change (admin only)
+    pub fn cancel_parameter_change(ctx: Context<CancelParameterChange>, change_id: u64) -> Result<()> {
+        instructions::cancel_parameter_change(ctx, change_id)
+    }
+
+    /// Set up stake-weighted governance (admin only, once)
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        voting_period: i64,
+        quorum_bps: u16,
+        proposal_threshold: u64,
+        execution_grace_period: i64,
+    ) -> Result<()> {
+        instructions::initialize_governance(ctx, voting_period, quorum_bps, proposal_threshold, execution_grace_period)
+    }
+
+    /// Open a proposal backed by one of the proposer's positions
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: state::ProposalAction) -> Result<()> {
+        instructions::create_proposal(ctx, action)
+    }
+
+    /// Vote on a proposal with one position's stake
+    pub fn cast_vote(ctx: Context<CastVote>, proposal_id: u64, support: bool) -> Result<()> {
+        instructions::cast_vote(ctx, proposal_id, support)
+    }
+
+    /// Withdraw a proposal while voting is open (proposer only)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>, proposal_id: u64) -> Result<()> {
+        instructions::cancel_proposal(ctx, proposal_id)
+    }
+
+    /// Execute a passed proposal once voting has closed (permissionless)
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>, proposal_id: u64) -> Result<()> {
+        instructions::execute_proposal(ctx, proposal_id)
+    }
+
+    /// Reclaim a vote record's rent once voting has ended (voter only)
+    pub fn close_vote_record(ctx: Context<CloseVoteRecord>, proposal_id: u64) -> Result<()> {
+        instructions::close_vote_record(ctx, proposal_id)
+    }
+
+    /// Start the voting history of a position opened before checkpoints (permissionless)
+    pub fn checkpoint_stake(ctx: Context<CheckpointStake>) -> Result<()> {
+        instructions::checkpoint_stake(ctx)
+    }
+
+    /// Look up a position's voting power at a past time (view)
+    pub fn voting_power_at(ctx: Context<VotingPowerAt>, timestamp: i64) -> Result<u64> {
+        instructions::voting_power_at(ctx, timestamp)
+    }
+
+    /// Nominate a new authority, e.g. a multisig (admin only)
+    pub fn propose_authority(ctx: Context<AdminControl>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    /// Complete an authority transfer (pending authority only)
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    /// Withdraw a pending authority nomination (admin only)
+    pub fn cancel_authority_transfer(ctx: Context<AdminControl>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    /// Fund the reward vault with tokens (admin or treasurer)
     pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
         instructions::fund_rewards(ctx, amount)
     }
+
+    /// Fund a pool's rewards for a fixed period, rolling over any unemitted
+    /// part of its running campaign (admin or treasurer)
+    pub fn notify_reward_amount(
+        ctx: Context<NotifyRewardAmount>,
+        pool_id: u8,
+        amount: u64,
+        duration: i64,
+    ) -> Result<()> {
+        instructions::notify_reward_amount(ctx, pool_id, amount, duration)
+    }
+
+    /// Split governance fees between several recipients, including burning (admin only)
+    pub fn set_fee_distribution<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetFeeDistribution<'info>>,
+        recipients: Vec<state::FeeRecipient>,
+    ) -> Result<()> {
+        instructions::set_fee_distribution(ctx, recipients)
+    }
+
+    /// Send the whole governance fee to the treasury again (admin only)
+    pub fn clear_fee_distribution(ctx: Context<AdminControl>) -> Result<()> {
+        instructions::clear_fee_distribution(ctx)
+    }
+
+    /// Set the share of a referred staker's governance fee paid to the referrer (admin only)
+    pub fn set_referral_share(ctx: Context<AdminControl>, share_bps: u16) -> Result<()> {
+        instructions::set_referral_share(ctx, share_bps)
+    }
+
+    /// Open a referrer account that stakers can name when staking
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer(ctx)
+    }
+
+    /// Withdraw accrued referral rewards
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        instructions::claim_referral_rewards(ctx)
+    }
+
+    /// Set the treasury's per-epoch spending caps (admin only)
+    pub fn set_treasury_limits(
+        ctx: Context<ConfigureTreasury>,
+        epoch_duration: i64,
+        epoch_cap: u64,
+        penalty_epoch_cap: u64,
+    ) -> Result<()> {
+        instructions::set_treasury_limits(ctx, epoch_duration, epoch_cap, penalty_epoch_cap)
+    }
+
+    /// Allow treasury withdrawals to a token account (admin only)
+    pub fn add_treasury_recipient(ctx: Context<ConfigureTreasury>, recipient: Pubkey) -> Result<()> {
+        instructions::add_treasury_recipient(ctx, recipient)
+    }
+
+    /// Stop treasury withdrawals to a token account (admin only)
+    pub fn remove_treasury_recipient(ctx: Context<ConfigureTreasury>, recipient: Pubkey) -> Result<()> {
+        instructions::remove_treasury_recipient(ctx, recipient)
+    }
+
+    /// Withdraw governance fees to an allowlisted account (admin or treasurer)
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    /// Withdraw early-exit penalties to an allowlisted account (admin or treasurer)
+    pub fn withdraw_penalties(ctx: Context<WithdrawPenalties>, amount: u64) -> Result<()> {
+        instructions::withdraw_penalties(ctx, amount)
+    }
+
+    /// Recount available rewards from the reward vault balance (permissionless)
+    pub fn sync_reward_funding(ctx: Context<SyncRewardFunding>) -> Result<()> {
+        instructions::sync_reward_funding(ctx)
+    }
+
+    /// Add a co-incentive reward token to a pool (admin only)
+    pub fn add_reward_stream(
+        ctx: Context<AddRewardStream>,
+        pool_id: u8,
+        rate_per_second: u64,
+        stream_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::add_reward_stream(ctx, pool_id, rate_per_second, stream_authority)
+    }
+
+    /// Deposit tokens into a pool's reward stream vault
+    pub fn fund_reward_stream(
+        ctx: Context<FundRewardStream>,
+        pool_id: u8,
+        stream_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_reward_stream(ctx, pool_id, stream_index, amount)
+    }
+
+    /// Change a reward stream's rate (stream authority or admin)
+    pub fn set_reward_stream_rate(
+        ctx: Context<UpdateRewardStream>,
+        pool_id: u8,
+        stream_index: u8,
+        new_rate: u64,
+    ) -> Result<()> {
+        instructions::set_reward_stream_rate(ctx, pool_id, stream_index, new_rate)
+    }
+
+    /// Replace the flat reward rate with piecewise-constant epochs (admin only)
+    pub fn set_emission_epochs<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetEmissionSchedule<'info>>,
+        epochs: Vec<state::EmissionEpoch>,
+    ) -> Result<()> {
+        instructions::set_emission_epochs(ctx, epochs)
+    }
+
+    /// Replace the flat reward rate with a geometric step decay (admin only)
+    pub fn set_emission_decay<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetEmissionSchedule<'info>>,
+        initial_rate: u64,
+        start_time: i64,
+        decay_period: i64,
+        decay_bps: u16,
+    ) -> Result<()> {
+        instructions::set_emission_decay(ctx, initial_rate, start_time, decay_period, decay_bps)
+    }
+
+    /// Return to the flat reward rate (admin only)
+    pub fn clear_emission_schedule<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClearEmissionSchedule<'info>>,
+    ) -> Result<()> {
+        instructions::clear_emission_schedule(ctx)
+    }
+
+    /// Upgrade the economics account to the current layout (admin only)
+    pub fn migrate_economics(ctx: Context<MigrateEconomics>) -> Result<()> {
+        instructions::migrate_economics(ctx)
+    }
+
+    /// Upgrade a staking pool account to the current layout (admin only)
+    pub fn migrate_staking_pool(ctx: Context<MigrateStakingPool>, pool_id: u8) -> Result<()> {
+        instructions::migrate_staking_pool(ctx, pool_id)
+    }
+
+    /// Upgrade the caller's stake position to the current layout
+    pub fn migrate_user_stake(ctx: Context<MigrateUserStake>, pool_id: u8) -> Result<()> {
+        instructions::migrate_user_stake(ctx, pool_id)
+    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::EconomicsError;
 
 /// Main economics configuration and global state
 #[account]
@@ -41,7 +42,7 @@ pub struct TokenEconomics {
     /// Total rewards distributed to users
     pub total_rewards_distributed: u64,
     
-    /// Last time rewards were updated
+    /// Last time any pool accrued rewards (aggregate stat only; each pool keeps its own clock)
     pub last_reward_update_time: i64,
     
     /// Global accumulated reward per token
@@ -62,6 +63,49 @@ pub struct TokenEconomics {
     
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the initial release. New fields must be
+    // appended here so `migrate_economics` can zero-extend the legacy account.
+    /// Active emission schedule, or the default key to use `reward_rate_per_second`
+    pub emission_schedule: Pubkey,
+
+    /// Authority proposed by `propose_authority`, or the default key when no
+    /// transfer is in flight
+    pub pending_authority: Pubkey,
+
+    /// Seconds a queued parameter change must wait before it can be executed.
+    /// While non-zero, parameters can only be changed through the queue.
+    pub timelock_delay: i64,
+
+    /// Id assigned to the next queued parameter change
+    pub next_change_id: u64,
+
+    /// Primary rewards accrued to stakers but not yet paid out of `reward_vault`
+    pub committed_rewards: u64,
+
+    /// Tokens in `reward_vault` not yet accrued to anyone or reserved for a
+    /// campaign; accrual stops when this runs out
+    pub available_rewards: u64,
+
+    /// Estimate of when `available_rewards` runs out at the current base rate
+    /// (before pool multipliers), or `i64::MAX` while nothing is emitted
+    pub rewards_funded_until: i64,
+
+    /// Active governance fee split, or the default key to send the whole fee
+    /// to `treasury_vault`
+    pub fee_distribution: Pubkey,
+
+    /// Share of a referred staker's governance fee credited to their referrer,
+    /// in basis points
+    pub referral_share_bps: u16,
+
+    /// Tokens in `reward_vault` reserved for pools' reward campaigns, summed
+    /// over every pool's `campaign_reserved`
+    pub campaign_reserved: u64,
+
+    /// Bit `pool_id` is set for every existing staking pool, so rate changes
+    /// can require all of them to be settled
+    pub pool_ids: [u64; 4],
 }
 
 impl TokenEconomics {
@@ -84,7 +128,596 @@ impl TokenEconomics {
         1 +  // is_paused
         1 +  // emergency_mode
         8 +  // created_at
+        1 +  // bump
+        32 + // emission_schedule
+        32 + // pending_authority
+        8 +  // timelock_delay
+        8 +  // next_change_id
+        8 +  // committed_rewards
+        8 +  // available_rewards
+        8 +  // rewards_funded_until
+        32 + // fee_distribution
+        2 +  // referral_share_bps
+        8 +  // campaign_reserved
+        32;  // pool_ids
+
+    /// Upper bound for `timelock_delay` so a typo cannot freeze configuration
+    pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+    /// Word and bit of `pool_id` in a pool id bitmap such as `pool_ids`
+    pub fn pool_bit(pool_id: u8) -> (usize, u64) {
+        (pool_id as usize / 64, 1 << (pool_id % 64))
+    }
+
+    /// Marks a pool as existing or removed
+    pub fn set_pool(&mut self, pool_id: u8, exists: bool) {
+        let (word, bit) = Self::pool_bit(pool_id);
+        if exists {
+            self.pool_ids[word] |= bit;
+        } else {
+            self.pool_ids[word] &= !bit;
+        }
+    }
+}
+
+/// Reward emission schedule that replaces the flat base rate while active
+#[account]
+pub struct EmissionSchedule {
+    /// Economics account this schedule drives
+    pub economics: Pubkey,
+
+    /// Which of the parameter sets below is in use
+    pub kind: EmissionKind,
+
+    /// Piecewise-constant rates sorted by start time (`EmissionKind::Epochs`).
+    /// Nothing is emitted before the first epoch; the last epoch runs forever.
+    pub epochs: Vec<EmissionEpoch>,
+
+    /// Rate per second of the first decay period (`EmissionKind::Decay`)
+    pub initial_rate: u64,
+
+    /// When the first decay period starts; nothing is emitted before it
+    pub start_time: i64,
+
+    /// Length of each decay period in seconds
+    pub decay_period: i64,
+
+    /// Rate reduction applied at each period boundary (5000 = halving)
+    pub decay_bps: u16,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        1 +  // kind
+        4 + EmissionEpoch::LEN * EmissionSchedule::MAX_EPOCHS + // epochs
+        8 +  // initial_rate
+        8 +  // start_time
+        8 +  // decay_period
+        2 +  // decay_bps
+        1;   // bump
+
+    /// Maximum number of epochs in a piecewise schedule
+    pub const MAX_EPOCHS: usize = 16;
+
+    /// Switches to piecewise-constant epochs, clearing the decay parameters
+    pub fn set_epochs(&mut self, epochs: Vec<EmissionEpoch>) {
+        self.kind = EmissionKind::Epochs;
+        self.epochs = epochs;
+        self.initial_rate = 0;
+        self.start_time = 0;
+        self.decay_period = 0;
+        self.decay_bps = 0;
+    }
+
+    /// Switches to a geometric step decay, clearing the epochs
+    pub fn set_decay(&mut self, initial_rate: u64, start_time: i64, decay_period: i64, decay_bps: u16) {
+        self.kind = EmissionKind::Decay;
+        self.epochs = Vec::new();
+        self.initial_rate = initial_rate;
+        self.start_time = start_time;
+        self.decay_period = decay_period;
+        self.decay_bps = decay_bps;
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EmissionKind {
+    /// Piecewise-constant epochs
+    Epochs,
+    /// Geometric step decay every `decay_period` seconds
+    Decay,
+}
+
+/// One piece of a piecewise emission schedule
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct EmissionEpoch {
+    /// When this rate takes effect
+    pub start_time: i64,
+
+    /// Base reward rate per second from `start_time` until the next epoch
+    pub rate_per_second: u64,
+}
+
+impl EmissionEpoch {
+    pub const LEN: usize = 8 + // start_time
+        8;   // rate_per_second
+}
+
+/// Parameter change waiting out the economics timelock
+#[account]
+pub struct PendingChange {
+    /// Economics account the change applies to
+    pub economics: Pubkey,
+
+    /// Sequential id, also part of the PDA seeds
+    pub change_id: u64,
+
+    /// The change to apply
+    pub change: ParameterChange,
+
+    /// Earliest time the change can be executed
+    pub eta: i64,
+
+    /// Who queued the change and receives the rent back
+    pub proposer: Pubkey,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PendingChange {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        8 +  // change_id
+        ParameterChange::LEN + // change
+        8 +  // eta
+        32 + // proposer
+        1;   // bump
+}
+
+/// Configuration changes that go through the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ParameterChange {
+    RewardRate { new_rate: u64 },
+    GovernanceFee { fee_bps: u16 },
+    StakeLimits { min_stake_amount: u64, max_stake_amount: u64 },
+    TimelockDelay { delay: i64 },
+    PoolMultiplier { pool_id: u8, reward_multiplier: u16 },
+    PoolMinStakePeriod { pool_id: u8, min_stake_period: i64 },
+    PoolCapacity { pool_id: u8, max_capacity: u64 },
+    PoolUnbondingPeriod { pool_id: u8, unbonding_period: i64 },
+    PoolEarlyExitPenalty {
+        pool_id: u8,
+        penalty_mode: PenaltyMode,
+        penalty_bps: u16,
+        penalty_destination: PenaltyDestination,
+    },
+    EmissionEpochs { epochs: Vec<EmissionEpoch> },
+    EmissionDecay { initial_rate: u64, start_time: i64, decay_period: i64, decay_bps: u16 },
+    ClearEmissionSchedule,
+    PoolNftBoost { pool_id: u8, collection: Pubkey, boost_bps: u16 },
+    PoolLoyaltyTiers { pool_id: u8, tiers: Vec<LoyaltyTier> },
+}
+
+impl ParameterChange {
+    /// Variant tag plus the largest payload (`EmissionEpochs`)
+    pub const LEN: usize = 1 + 4 + EmissionEpoch::LEN * EmissionSchedule::MAX_EPOCHS;
+
+    /// Pool targeted by the change, if any
+    pub fn pool_id(&self) -> Option<u8> {
+        match self {
+            ParameterChange::PoolMultiplier { pool_id, .. }
+            | ParameterChange::PoolMinStakePeriod { pool_id, .. }
+            | ParameterChange::PoolCapacity { pool_id, .. }
+            | ParameterChange::PoolUnbondingPeriod { pool_id, .. }
+            | ParameterChange::PoolEarlyExitPenalty { pool_id, .. }
+            | ParameterChange::PoolNftBoost { pool_id, .. }
+            | ParameterChange::PoolLoyaltyTiers { pool_id, .. } => Some(*pool_id),
+            _ => None,
+        }
+    }
+
+    /// Role allowed to queue the change besides the authority; `None` means
+    /// authority only
+    pub fn required_role(&self) -> Option<Role> {
+        match self {
+            ParameterChange::RewardRate { .. } => Some(Role::RateManager),
+            ParameterChange::PoolMultiplier { .. }
+            | ParameterChange::PoolMinStakePeriod { .. }
+            | ParameterChange::PoolCapacity { .. }
+            | ParameterChange::PoolUnbondingPeriod { .. }
+            | ParameterChange::PoolEarlyExitPenalty { .. }
+            | ParameterChange::PoolNftBoost { .. }
+            | ParameterChange::PoolLoyaltyTiers { .. } => Some(Role::PoolManager),
+            _ => None,
+        }
+    }
+}
+
+/// Operational roles delegated by the economics authority to one member
+#[account]
+pub struct RoleAssignment {
+    /// Economics account the roles apply to
+    pub economics: Pubkey,
+
+    /// Wallet holding the roles
+    pub member: Pubkey,
+
+    /// Bitmask of granted `Role`s
+    pub roles: u8,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl RoleAssignment {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        32 + // member
+        1 +  // roles
+        1;   // bump
+
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles & role.mask() != 0
+    }
+}
+
+/// Admin capabilities that can be granted without handing over the authority key.
+/// The economics authority implicitly holds every role.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Pause/unpause the system and toggle emergency mode
+    Pauser,
+    /// Change the base reward rate
+    RateManager,
+    /// Create staking pools
+    PoolManager,
+    /// Fund the reward vault, start reward campaigns and withdraw from the
+    /// treasury and the penalty vault within their limits
+    Treasurer,
+}
+
+impl Role {
+    pub fn mask(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// How the governance fee taken from claims is split between recipients
+#[account]
+pub struct FeeDistribution {
+    /// Economics account whose fees are split
+    pub economics: Pubkey,
+
+    /// Shares of the fee; weights add up to 10000 and the last recipient also
+    /// takes the rounding remainder
+    pub recipients: Vec<FeeRecipient>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl FeeDistribution {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        4 + FeeRecipient::LEN * FeeDistribution::MAX_RECIPIENTS + // recipients
+        1;   // bump
+
+    pub const MAX_RECIPIENTS: usize = 5;
+
+    /// Number of token accounts a claim has to pass for the external recipients
+    pub fn token_account_count(&self) -> usize {
+        self.recipients
+            .iter()
+            .filter(|recipient| matches!(recipient.destination, FeeDestination::TokenAccount { .. }))
+            .count()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FeeRecipient {
+    pub destination: FeeDestination,
+
+    /// Share of the fee in basis points
+    pub bps: u16,
+}
+
+impl FeeRecipient {
+    pub const LEN: usize = 1 + 32 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeDestination {
+    /// The protocol's `treasury_vault`
+    Treasury,
+    /// Any reward-token account, e.g. an insurance fund
+    TokenAccount { address: Pubkey },
+    /// Burned out of the reward vault, reducing supply
+    Burn,
+}
+
+/// Limits on moving governance fees out of `treasury_vault`
+#[account]
+pub struct TreasuryConfig {
+    /// Economics account whose treasury is limited
+    pub economics: Pubkey,
+
+    /// Length of a spending epoch in seconds
+    pub epoch_duration: i64,
+
+    /// Most that can be withdrawn per epoch; 0 blocks withdrawals
+    pub epoch_cap: u64,
+
+    /// Start of the epoch `spent_this_epoch` belongs to
+    pub current_epoch_start: i64,
+
+    /// Withdrawn so far in the current epoch
+    pub spent_this_epoch: u64,
+
+    /// Most early-exit penalties that can be withdrawn per epoch; 0 blocks
+    /// penalty withdrawals
+    pub penalty_epoch_cap: u64,
+
+    /// Penalties withdrawn so far in the current epoch
+    pub penalty_spent_this_epoch: u64,
+
+    /// Token accounts withdrawals may be sent to
+    pub allowed_recipients: Vec<Pubkey>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl TreasuryConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        8 +  // epoch_duration
+        8 +  // epoch_cap
+        8 +  // current_epoch_start
+        8 +  // spent_this_epoch
+        8 +  // penalty_epoch_cap
+        8 +  // penalty_spent_this_epoch
+        4 + 32 * TreasuryConfig::MAX_RECIPIENTS + // allowed_recipients
+        1;   // bump
+
+    /// Maximum number of allowlisted recipients
+    pub const MAX_RECIPIENTS: usize = 10;
+
+    /// Rolls over to the epoch containing `now`, keeping epochs aligned to the
+    /// first one
+    pub fn roll_epoch(&mut self, now: i64) {
+        let elapsed = now - self.current_epoch_start;
+        if elapsed >= self.epoch_duration {
+            self.current_epoch_start = now - elapsed % self.epoch_duration;
+            self.spent_this_epoch = 0;
+            self.penalty_spent_this_epoch = 0;
+        }
+    }
+}
+
+/// Stake-weighted governance settings, created once by the authority
+#[account]
+pub struct GovernanceConfig {
+    /// Economics account governed
+    pub economics: Pubkey,
+
+    /// Seconds a proposal stays open for voting
+    pub voting_period: i64,
+
+    /// Share of the total stake at creation that must vote, in basis points
+    pub quorum_bps: u16,
+
+    /// Voting power the proposer's position needs to open a proposal
+    pub proposal_threshold: u64,
+
+    /// Seconds after voting ends during which a passed proposal can still be
+    /// executed; afterwards it expires
+    pub execution_grace_period: i64,
+
+    /// Id assigned to the next proposal
+    pub next_proposal_id: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+
+    /// Bump of the `[b"governance_authority"]` signer that executes proposals.
+    /// It acts like any other member: grant it roles, or make it the authority.
+    pub authority_bump: u8,
+}
+
+impl GovernanceConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // economics
+        8 +  // voting_period
+        2 +  // quorum_bps
+        8 +  // proposal_threshold
+        8 +  // execution_grace_period
+        8 +  // next_proposal_id
+        1 +  // bump
+        1;   // authority_bump
+
+    /// Longest voting period a proposal can have (30 days)
+    pub const MAX_VOTING_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+    /// Longest execution grace period (30 days)
+    pub const MAX_EXECUTION_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
+}
+
+/// A governance proposal and its running tally
+#[account]
+pub struct Proposal {
+    /// Sequential id of the proposal
+    pub proposal_id: u64,
+
+    /// Who opened the proposal
+    pub proposer: Pubkey,
+
+    /// What executing the proposal does
+    pub action: ProposalAction,
+
+    /// When the proposal was opened; voting power is read from the stake
+    /// checkpoints just before this time
+    pub created_at: i64,
+
+    /// When voting closes and execution becomes possible
+    pub voting_ends_at: i64,
+
+    /// Voting power in favour
+    pub for_votes: u64,
+
+    /// Voting power against
+    pub against_votes: u64,
+
+    /// Participation needed for the result to count
+    pub quorum_votes: u64,
+
+    /// Whether the proposal has been executed
+    pub executed: bool,
+
+    /// Whether the proposer withdrew the proposal
+    pub cancelled: bool,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + // discriminator
+        8 +  // proposal_id
+        32 + // proposer
+        ProposalAction::LEN + // action
+        8 +  // created_at
+        8 +  // voting_ends_at
+        8 +  // for_votes
+        8 +  // against_votes
+        8 +  // quorum_votes
+        1 +  // executed
+        1 +  // cancelled
         1;   // bump
+
+    /// Closed, quorate and more for than against
+    pub fn has_passed(&self) -> bool {
+        let turnout = self.for_votes as u128 + self.against_votes as u128;
+        turnout >= self.quorum_votes as u128 && self.for_votes > self.against_votes
+    }
+}
+
+/// What a passed proposal does when executed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum ProposalAction {
+    /// Queue a parameter change; the governance signer needs the change's role
+    Parameter { change: ParameterChange },
+    /// Pay governance fees out of the treasury; the governance signer needs `Treasurer`
+    TreasurySpend { recipient: Pubkey, amount: u64 },
+    /// Take over the economics authority after it was proposed to the governance signer
+    AcceptAuthority,
+}
+
+impl ProposalAction {
+    /// Variant tag plus the largest payload (`Parameter`)
+    pub const LEN: usize = 1 + ParameterChange::LEN;
+}
+
+/// One position's vote on a proposal; its existence prevents voting twice
+#[account]
+pub struct VoteRecord {
+    /// Proposal voted on
+    pub proposal: Pubkey,
+
+    /// Position whose stake was counted
+    pub user_stake: Pubkey,
+
+    /// Owner of the position
+    pub voter: Pubkey,
+
+    /// Whether the vote was in favour
+    pub support: bool,
+
+    /// Voting power counted
+    pub weight: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal
+        32 + // user_stake
+        32 + // voter
+        1 +  // support
+        8 +  // weight
+        1;   // bump
+}
+
+/// History of a position's stake, so voting power can be looked up at any
+/// past time instead of the current balance
+#[account]
+pub struct StakeCheckpoints {
+    /// Position the history belongs to
+    pub user_stake: Pubkey,
+
+    /// Whether older checkpoints were dropped to make room
+    pub truncated: bool,
+
+    /// Stake after each change, oldest first, at most one per timestamp
+    pub checkpoints: Vec<StakeCheckpoint>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl StakeCheckpoints {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user_stake
+        1 +  // truncated
+        4 + StakeCheckpoint::LEN * StakeCheckpoints::MAX_CHECKPOINTS + // checkpoints
+        1;   // bump
+
+    /// Checkpoints kept per position; older ones are dropped first
+    pub const MAX_CHECKPOINTS: usize = 64;
+
+    /// Stake held strictly before `timestamp`, so stake added in the same
+    /// second a proposal opens never counts. Binary search over the history.
+    pub fn voting_power_at(&self, timestamp: i64) -> Result<u64> {
+        let index = self.checkpoints.partition_point(|checkpoint| checkpoint.timestamp < timestamp);
+        match index.checked_sub(1) {
+            Some(latest) => Ok(self.checkpoints[latest].staked),
+            None if self.truncated => err!(EconomicsError::CheckpointHistoryUnavailable),
+            None => Ok(0),
+        }
+    }
+
+    /// Records the stake after a change at `timestamp`
+    pub fn push(&mut self, timestamp: i64, staked: u64) {
+        if let Some(latest) = self.checkpoints.last_mut() {
+            if latest.timestamp == timestamp {
+                latest.staked = staked;
+                return;
+            }
+        }
+        if self.checkpoints.len() == StakeCheckpoints::MAX_CHECKPOINTS {
+            self.checkpoints.remove(0);
+            self.truncated = true;
+        }
+        self.checkpoints.push(StakeCheckpoint { timestamp, staked });
+    }
+}
+
+/// A position's stake from `timestamp` until the next checkpoint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct StakeCheckpoint {
+    pub timestamp: i64,
+    pub staked: u64,
+}
+
+impl StakeCheckpoint {
+    pub const LEN: usize = 8 + // timestamp
+        8;   // staked
 }
 
 /// Individual staking pool with custom parameters
@@ -122,6 +755,63 @@ pub struct StakingPool {
     
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the initial release. New fields must be
+    // appended here so `migrate_staking_pool` can zero-extend legacy accounts.
+    /// Last time this pool's accumulator was advanced
+    pub last_reward_update_time: i64,
+
+    /// Sum of all positions' lock-boosted balances; rewards are shared pro rata to it
+    pub total_boosted: u64,
+
+    /// Additional co-incentive reward tokens, paid alongside the primary reward
+    pub reward_streams: Vec<RewardStream>,
+
+    /// Cooldown between `request_unstake` and `withdraw_unbonded`; 0 lets
+    /// `unstake_tokens` pay out immediately
+    pub unbonding_period: i64,
+
+    /// Tokens sitting in unbonding tickets; they no longer earn rewards
+    pub total_unbonding: u64,
+
+    /// Id assigned to the next unbonding ticket
+    pub next_ticket_id: u64,
+
+    /// Whether and how `unstake_early` may release locked lots
+    pub penalty_mode: PenaltyMode,
+
+    /// Penalty on locked stake released early, in basis points
+    pub penalty_bps: u16,
+
+    /// Where early-exit penalties go
+    pub penalty_destination: PenaltyDestination,
+
+    /// Primary reward rate of the pool's campaign; while a campaign has been
+    /// started it replaces the base rate and pool multiplier
+    pub campaign_rate_per_second: u64,
+
+    /// When the current campaign ends and accrual stops, or 0 if the pool
+    /// never ran one
+    pub campaign_end_time: i64,
+
+    /// Verified Metaplex collection whose holders get `nft_boost_bps`, or the
+    /// default key when the pool offers no NFT boost
+    pub nft_collection: Pubkey,
+
+    /// Extra weight in basis points for positions holding a collection NFT
+    pub nft_boost_bps: u16,
+
+    /// Extra weight for positions staked without interruption, sorted by
+    /// `min_duration`; the highest tier reached applies
+    pub loyalty_tiers: Vec<LoyaltyTier>,
+
+    /// User stake accounts of this pool that have not been closed; they may
+    /// still be owed rewards, so the pool cannot be closed while any remain
+    pub open_positions: u32,
+
+    /// Campaign funding not yet accrued; a campaign pool accrues only from
+    /// this, never from the shared `available_rewards`
+    pub campaign_reserved: u64,
 }
 
 impl StakingPool {
@@ -136,9 +826,222 @@ impl StakingPool {
         8 +  // accumulated_reward_per_token
         8 +  // created_at
         1 +  // is_active
+        1 +  // bump
+        8 +  // last_reward_update_time
+        8 +  // total_boosted
+        4 + RewardStream::LEN * StakingPool::MAX_REWARD_STREAMS + // reward_streams
+        8 +  // unbonding_period
+        8 +  // total_unbonding
+        8 +  // next_ticket_id
+        1 +  // penalty_mode
+        2 +  // penalty_bps
+        1 +  // penalty_destination
+        8 +  // campaign_rate_per_second
+        8 +  // campaign_end_time
+        32 + // nft_collection
+        2 +  // nft_boost_bps
+        4 + LoyaltyTier::LEN * StakingPool::MAX_LOYALTY_TIERS + // loyalty_tiers
+        4 +  // open_positions
+        8;   // campaign_reserved
+
+    /// Maximum number of additional reward streams per pool
+    pub const MAX_REWARD_STREAMS: usize = 4;
+
+    /// Longest unbonding period a pool can be configured with (30 days)
+    pub const MAX_UNBONDING_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+    /// Longest reward campaign `notify_reward_amount` can start (1 year)
+    pub const MAX_CAMPAIGN_DURATION: i64 = 365 * 24 * 60 * 60;
+
+    /// Largest NFT holder boost a pool can offer (2x)
+    pub const MAX_NFT_BOOST_BPS: u16 = 10_000;
+
+    /// Maximum number of loyalty tiers per pool
+    pub const MAX_LOYALTY_TIERS: usize = 4;
+
+    /// Largest loyalty bonus a tier can grant (2x)
+    pub const MAX_LOYALTY_BONUS_BPS: u16 = 10_000;
+
+    /// Loyalty bonus for a position that has held stake for `staked_for` seconds
+    pub fn loyalty_bonus_bps(&self, staked_for: i64) -> u16 {
+        self.loyalty_tiers
+            .iter()
+            .take_while(|tier| staked_for >= tier.min_duration)
+            .last()
+            .map_or(0, |tier| tier.bonus_bps)
+    }
+}
+
+/// A step of a pool's loyalty bonus
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct LoyaltyTier {
+    /// Seconds of uninterrupted staking needed to reach the tier
+    pub min_duration: i64,
+
+    /// Extra weight in basis points (1000 = +10%)
+    pub bonus_bps: u16,
+}
+
+impl LoyaltyTier {
+    pub const LEN: usize = 8 + 2;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PenaltyMode {
+    /// Locked lots cannot be released before `unlock_time`
+    Disabled,
+    /// `penalty_bps` of the locked amount, regardless of time left
+    Flat,
+    /// `penalty_bps` scaled by the share of the lock still remaining, reaching zero at unlock
+    Linear,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PenaltyDestination {
+    /// The protocol's stake-token penalty vault
+    Treasury,
+    /// Remaining stakers of the pool, through the reward accumulator.
+    /// Requires the stake and reward mints to be the same token.
+    Stakers,
+}
+
+/// Opts a position into permissionless compounding; the account existing is the flag
+#[account]
+pub struct AutoCompound {
+    /// Position that may be compounded by anyone
+    pub user_stake: Pubkey,
+
+    /// Owner of the position, who receives the rent back on opt-out
+    pub user: Pubkey,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl AutoCompound {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user_stake
+        32 + // user
         1;   // bump
 }
 
+/// Referral earnings of one referrer, paid out of `reward_vault` on
+/// `claim_referral_rewards`
+#[account]
+pub struct ReferrerAccount {
+    /// Wallet that receives the referral rewards
+    pub referrer: Pubkey,
+
+    /// Credited but not yet claimed; still counted in `committed_rewards`
+    pub pending_rewards: u64,
+
+    /// Everything ever credited
+    pub total_earned: u64,
+
+    /// Stakers bound to this referrer
+    pub referee_count: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ReferrerAccount {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // referrer
+        8 +  // pending_rewards
+        8 +  // total_earned
+        8 +  // referee_count
+        1;   // bump
+}
+
+/// Binds a staker to the referrer they first staked through; permanent
+#[account]
+pub struct Referral {
+    /// Referred staker
+    pub user: Pubkey,
+
+    /// Referrer credited on the staker's claims
+    pub referrer: Pubkey,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl Referral {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        32 + // referrer
+        1;   // bump
+}
+
+/// Stake taken out of a pool that is waiting out the pool's unbonding period
+#[account]
+pub struct UnbondingTicket {
+    /// Owner of the unbonding tokens
+    pub user: Pubkey,
+
+    /// Pool the tokens were unstaked from
+    pub pool_id: u8,
+
+    /// Pool-wide sequential id, also part of the PDA seeds
+    pub ticket_id: u64,
+
+    /// Tokens held by the ticket
+    pub amount: u64,
+
+    /// When the unbonding was requested
+    pub requested_at: i64,
+
+    /// Earliest time `withdraw_unbonded` pays out
+    pub withdrawable_at: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl UnbondingTicket {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        1 +  // pool_id
+        8 +  // ticket_id
+        8 +  // amount
+        8 +  // requested_at
+        8 +  // withdrawable_at
+        1;   // bump
+}
+
+/// An additional reward token distributed by a pool at its own rate
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct RewardStream {
+    /// Mint of the reward token
+    pub mint: Pubkey,
+
+    /// Program-owned vault the stream pays out from
+    pub vault: Pubkey,
+
+    /// Partner allowed to change this stream's rate
+    pub authority: Pubkey,
+
+    /// Tokens emitted per second across the whole pool
+    pub rate_per_second: u64,
+
+    /// Accumulated reward per boosted token (scaled by 1e12)
+    pub accumulated_reward_per_token: u64,
+
+    /// Tokens funded into `vault` not yet accrued to stakers; the stream stops
+    /// accruing when this runs out
+    pub available_rewards: u64,
+}
+
+impl RewardStream {
+    pub const LEN: usize = 32 + // mint
+        32 + // vault
+        32 + // authority
+        8 +  // rate_per_second
+        8 +  // accumulated_reward_per_token
+        8;   // available_rewards
+}
+
 /// Individual user's stake information for a specific pool
 #[account]
 pub struct UserStake {
@@ -160,7 +1063,8 @@ pub struct UserStake {
     /// Total rewards claimed by this user
     pub total_rewards_claimed: u64,
     
-    /// When user first staked in this pool
+    /// When the position last went from no stake to some; drives the pool's
+    /// loyalty bonus, so a full unstake starts it over
     pub first_stake_time: i64,
     
     /// When user last staked tokens
@@ -171,6 +1075,28 @@ pub struct UserStake {
     
     /// PDA bump seed
     pub bump: u8,
+
+    // Fields below were added after the initial release. New fields must be
+    // appended here so `migrate_user_stake` can zero-extend legacy accounts.
+    /// Id assigned to the next deposit lot
+    pub next_lot_id: u32,
+
+    /// Open deposit lots, oldest first; their amounts sum to `total_staked`
+    pub lots: Vec<StakeLot>,
+
+    /// Stake weighted by remaining lock time as of the last refresh
+    pub boosted_balance: u64,
+
+    /// Per-stream reward state, index-aligned with the pool's `reward_streams`
+    pub stream_rewards: Vec<UserStreamReward>,
+
+    /// Token account whose collection NFT earned the pool's NFT boost, or the
+    /// default key when the position has none
+    pub nft_boost_account: Pubkey,
+
+    /// Collection the NFT boost was verified against; the boost lapses once
+    /// the pool switches to another collection
+    pub nft_boost_collection: Pubkey,
 }
 
 impl UserStake {
@@ -184,5 +1110,64 @@ impl UserStake {
         8 +  // first_stake_time
         8 +  // last_stake_time
         8 +  // last_claim_time
-        1;   // bump
+        1 +  // bump
+        4 +  // next_lot_id
+        4 + StakeLot::LEN * UserStake::MAX_LOTS + // lots
+        8 +  // boosted_balance
+        4 + UserStreamReward::LEN * StakingPool::MAX_REWARD_STREAMS + // stream_rewards
+        32 + // nft_boost_account
+        32;  // nft_boost_collection
+
+    /// Maximum number of open deposit lots per position
+    pub const MAX_LOTS: usize = 16;
+
+    /// Shortest lock that can be chosen at stake time (1 week)
+    pub const MIN_LOCK_DURATION: i64 = 7 * 24 * 60 * 60;
+
+    /// Longest lock that can be chosen at stake time (4 years)
+    pub const MAX_LOCK_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+
+    /// Extra weight in basis points for stake with the maximum lock remaining (2.5x total)
+    pub const MAX_LOCK_BOOST_BPS: u64 = 15_000;
+
+    /// Most positions `claim_all` settles in one transaction
+    pub const MAX_CLAIM_ALL_POSITIONS: usize = 10;
+}
+
+/// A position's reward bookkeeping for one additional reward stream
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct UserStreamReward {
+    /// Stream rewards earned but not yet claimed
+    pub pending_rewards: u64,
+
+    /// Reward debt against the stream's accumulator
+    pub reward_debt: u64,
+}
+
+impl UserStreamReward {
+    pub const LEN: usize = 8 + // pending_rewards
+        8;   // reward_debt
+}
+
+/// A single deposit within a stake position, locked until its own unlock time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct StakeLot {
+    /// Sequential id of the deposit within the position
+    pub lot_id: u32,
+
+    /// Amount of this deposit still staked
+    pub amount: u64,
+
+    /// When the deposit was made
+    pub deposit_time: i64,
+
+    /// When the deposit can be unstaked
+    pub unlock_time: i64,
+}
+
+impl StakeLot {
+    pub const LEN: usize = 4 + // lot_id
+        8 +  // amount
+        8 +  // deposit_time
+        8;   // unlock_time
 }
\ No newline at end of file
@@ -1,72 +1,1187 @@
 use anchor_lang::prelude::*;
-use crate::state::{TokenEconomics, StakingPool, UserStake};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_interface::{self, spl_token_2022, Burn, Mint, TokenAccount, TransferChecked};
+use anchor_spl::token_interface::spl_token_2022::extension::{
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use crate::state::{
+    RoleAssignment, Role, PenaltyMode, PenaltyDestination, TokenEconomics, StakingPool, UserStake, StakeLot, EmissionSchedule, EmissionEpoch, EmissionKind,
+    UserStreamReward, StakeCheckpoints, FeeDistribution, FeeDestination, Referral, ReferrerAccount, LoyaltyTier,
+};
+use crate::events::{ConsumedLot, ReferralRewardAccruedEvent, NftBoostChangedEvent};
 use crate::errors::EconomicsError;
 
-/// Updates the accumulated reward per token based on time elapsed
+/// Metaplex Token Metadata program, owner of the metadata accounts NFT boosts
+/// are checked against
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Fixed-point scale for emission decay factors
+const DECAY_SCALE: u128 = 1_000_000_000_000;
+
+/// Token-2022 extensions the vault accounting cannot support: non-transferable
+/// tokens could never leave a vault, a permanent delegate could drain one, and
+/// transfer hooks need extra accounts the program does not forward
+const UNSUPPORTED_MINT_EXTENSIONS: [ExtensionType; 3] = [
+    ExtensionType::NonTransferable,
+    ExtensionType::PermanentDelegate,
+    ExtensionType::TransferHook,
+];
+
+/// Checks that the passed schedule is the active one, so callers cannot
+/// dodge an active schedule by omitting it
+pub fn validate_emission_schedule(
+    economics: &TokenEconomics,
+    emission_schedule: Option<&Account<EmissionSchedule>>,
+) -> Result<()> {
+    match emission_schedule {
+        Some(schedule) => require_keys_eq!(
+            schedule.key(),
+            economics.emission_schedule,
+            EconomicsError::EmissionScheduleMismatch
+        ),
+        None => require_keys_eq!(
+            economics.emission_schedule,
+            Pubkey::default(),
+            EconomicsError::EmissionScheduleMismatch
+        ),
+    }
+    Ok(())
+}
+
+/// Checks that the passed fee split is the active one, so claims cannot send
+/// the whole fee to the treasury by omitting it
+pub fn validate_fee_distribution(
+    economics: &TokenEconomics,
+    fee_distribution: Option<&Account<FeeDistribution>>,
+) -> Result<()> {
+    match fee_distribution {
+        Some(distribution) => require_keys_eq!(
+            distribution.key(),
+            economics.fee_distribution,
+            EconomicsError::FeeDistributionMismatch
+        ),
+        None => require_keys_eq!(
+            economics.fee_distribution,
+            Pubkey::default(),
+            EconomicsError::FeeDistributionMismatch
+        ),
+    }
+    Ok(())
+}
+
+/// Accounts a governance fee is paid out with
+pub struct FeeAccounts<'a, 'info> {
+    /// Vault the fee is taken from
+    pub vault: &'a AccountInfo<'info>,
+    pub treasury_vault: &'a AccountInfo<'info>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    /// Economics PDA owning `vault`
+    pub authority: &'a AccountInfo<'info>,
+    pub token_program: &'a AccountInfo<'info>,
+    /// One token account per `FeeDestination::TokenAccount`, in configuration order
+    pub recipients: &'a [AccountInfo<'info>],
+}
+
+/// Pays a governance fee out of the vault according to the fee split, or
+/// entirely to the treasury when no split is active
+pub fn distribute_governance_fee<'info>(
+    fee: u64,
+    fee_distribution: Option<&FeeDistribution>,
+    accounts: FeeAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let transfer = |to: &AccountInfo<'info>, amount: u64| -> Result<()> {
+        let cpi_accounts = TransferChecked {
+            from: accounts.vault.clone(),
+            mint: accounts.mint.to_account_info(),
+            to: to.clone(),
+            authority: accounts.authority.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(accounts.token_program.clone(), cpi_accounts, signer_seeds);
+        token_interface::transfer_checked(cpi_ctx, amount, accounts.mint.decimals)
+    };
+
+    let Some(fee_distribution) = fee_distribution else {
+        require!(accounts.recipients.is_empty(), EconomicsError::InvalidFeeRecipientAccounts);
+        if fee > 0 {
+            transfer(accounts.treasury_vault, fee)?;
+        }
+        return Ok(());
+    };
+    require!(
+        accounts.recipients.len() == fee_distribution.token_account_count(),
+        EconomicsError::InvalidFeeRecipientAccounts
+    );
+
+    let mut remaining = fee;
+    let mut recipient_accounts = accounts.recipients.iter();
+    let last = fee_distribution.recipients.len() - 1;
+    for (index, recipient) in fee_distribution.recipients.iter().enumerate() {
+        let share = if index == last {
+            remaining
+        } else {
+            (fee as u128 * recipient.bps as u128 / 10000) as u64
+        };
+        remaining = remaining.checked_sub(share).ok_or(EconomicsError::MathOverflow)?;
+
+        let destination = match recipient.destination {
+            FeeDestination::TokenAccount { address } => {
+                let account = recipient_accounts.next().ok_or(EconomicsError::InvalidFeeRecipientAccounts)?;
+                require_keys_eq!(account.key(), address, EconomicsError::InvalidFeeRecipientAccounts);
+                Some(account)
+            }
+            FeeDestination::Treasury => Some(accounts.treasury_vault),
+            FeeDestination::Burn => None,
+        };
+        if share == 0 {
+            continue;
+        }
+        match destination {
+            Some(to) => transfer(to, share)?,
+            None => {
+                let cpi_accounts = Burn {
+                    mint: accounts.mint.to_account_info(),
+                    from: accounts.vault.clone(),
+                    authority: accounts.authority.clone(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(accounts.token_program.clone(), cpi_accounts, signer_seeds);
+                token_interface::burn(cpi_ctx, share)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Credits the referrer's cut of a referred staker's governance fee. The cut
+/// stays in `reward_vault` as committed rewards until the referrer claims it.
+/// Returns the amount taken out of `governance_fee`.
+pub fn credit_referral(
+    economics: &TokenEconomics,
+    referral: &AccountInfo,
+    referrer_account: Option<&mut Account<ReferrerAccount>>,
+    referee: Pubkey,
+    governance_fee: u64,
+) -> Result<u64> {
+    // The referral PDA only exists once the staker staked through a referrer
+    if referral.data_is_empty() {
+        return Ok(0);
+    }
+    require_keys_eq!(*referral.owner, crate::ID, EconomicsError::InvalidReferralAccounts);
+    let referral = Referral::try_deserialize(&mut &referral.try_borrow_data()?[..])?;
+    let referrer_account = referrer_account.ok_or(EconomicsError::InvalidReferralAccounts)?;
+    require_keys_eq!(referrer_account.referrer, referral.referrer, EconomicsError::InvalidReferralAccounts);
+
+    let share = (governance_fee as u128 * economics.referral_share_bps as u128 / 10000) as u64;
+    if share > 0 {
+        referrer_account.pending_rewards = referrer_account.pending_rewards
+            .checked_add(share)
+            .ok_or(EconomicsError::MathOverflow)?;
+        referrer_account.total_earned = referrer_account.total_earned
+            .checked_add(share)
+            .ok_or(EconomicsError::MathOverflow)?;
+
+        emit!(ReferralRewardAccruedEvent {
+            referrer: referral.referrer,
+            referee,
+            amount: share,
+        });
+    }
+    Ok(share)
+}
+
+/// Updates the pool's accumulated reward per token based on the time elapsed
+/// since the pool's own last update, so pools accrue independently.
+/// Accrual is capped at the funded, not yet committed rewards.
 pub fn update_reward_accumulation(
     economics: &mut Account<TokenEconomics>,
     pool: &mut Account<StakingPool>,
+    emission_schedule: Option<&Account<EmissionSchedule>>,
 ) -> Result<()> {
+    validate_emission_schedule(economics, emission_schedule)?;
+
     let current_time = Clock::get()?.unix_timestamp;
-    let time_delta = current_time - economics.last_reward_update_time;
+    let time_delta = current_time - pool.last_reward_update_time;
     
-    if time_delta > 0 && pool.total_staked > 0 {
-        // Calculate rewards for this time period
-        let base_rewards = (economics.reward_rate_per_second as u128)
-            .checked_mul(time_delta as u128)
-            .ok_or(EconomicsError::MathOverflow)?;
+    if time_delta > 0 && pool.total_boosted > 0 {
+        // A campaign pool emits its own rate until the campaign ends, paid from
+        // its own reserve; other pools apply their multiplier to the base
+        // emission, paid from the shared funding
+        let pool_rewards = if pool.campaign_end_time > 0 {
+            let campaign_end = current_time.min(pool.campaign_end_time);
+            let campaign_rewards = (pool.campaign_rate_per_second as u128)
+                .checked_mul(campaign_end.saturating_sub(pool.last_reward_update_time).max(0) as u128)
+                .ok_or(EconomicsError::MathOverflow)?
+                .min(pool.campaign_reserved as u128) as u64;
+            commit_campaign_rewards(economics, pool, campaign_rewards)?;
+            campaign_rewards as u128
+        } else {
+            let base_rewards = calculate_base_emission(
+                economics,
+                emission_schedule.map(|schedule| &**schedule),
+                pool.last_reward_update_time,
+                current_time,
+            )?;
+            // Scale down to what is still funded
+            let pool_rewards = base_rewards
+                .checked_mul(pool.reward_multiplier as u128)
+                .ok_or(EconomicsError::MathOverflow)?
+                .checked_div(100)
+                .ok_or(EconomicsError::MathOverflow)?
+                .min(economics.available_rewards as u128);
+            commit_rewards(economics, pool_rewards as u64)?;
+            pool_rewards
+        };
             
-        // Apply pool multiplier
-        let pool_rewards = base_rewards
-            .checked_mul(pool.reward_multiplier as u128)
-            .ok_or(EconomicsError::MathOverflow)?
-            .checked_div(100)
-            .ok_or(EconomicsError::MathOverflow)?;
-            
-        // Update accumulated reward per token (scaled by 1e12 for precision)
+        // Update accumulated reward per boosted token (scaled by 1e12 for precision)
         let reward_per_token_delta = pool_rewards
             .checked_mul(1_000_000_000_000)
             .ok_or(EconomicsError::MathOverflow)?
-            .checked_div(pool.total_staked as u128)
+            .checked_div(pool.total_boosted as u128)
             .ok_or(EconomicsError::MathOverflow)?;
             
         pool.accumulated_reward_per_token = pool.accumulated_reward_per_token
             .checked_add(reward_per_token_delta as u64)
             .ok_or(EconomicsError::MathOverflow)?;
+
+        // Co-incentive streams emit at their own flat rate, without the pool
+        // multiplier, until their own funding runs out
+        let total_boosted = pool.total_boosted as u128;
+        for stream in pool.reward_streams.iter_mut() {
+            let stream_rewards = (stream.rate_per_second as u128)
+                .checked_mul(time_delta as u128)
+                .ok_or(EconomicsError::MathOverflow)?
+                .min(stream.available_rewards as u128) as u64;
+            stream.available_rewards -= stream_rewards;
+
+            let stream_per_token_delta = (stream_rewards as u128)
+                .checked_mul(1_000_000_000_000)
+                .ok_or(EconomicsError::MathOverflow)?
+                .checked_div(total_boosted)
+                .ok_or(EconomicsError::MathOverflow)?;
+            stream.accumulated_reward_per_token = stream.accumulated_reward_per_token
+                .checked_add(stream_per_token_delta as u64)
+                .ok_or(EconomicsError::MathOverflow)?;
+        }
     }
     
+    pool.last_reward_update_time = current_time;
     economics.last_reward_update_time = current_time;
+    refresh_rewards_funded_until(economics, emission_schedule.map(|schedule| &**schedule), current_time)?;
+    Ok(())
+}
+
+/// Settles every pool under the rate and schedule still in effect, so a
+/// following rate change only applies from now on. `pool_accounts` must hold
+/// each existing pool exactly once; a pool left out would otherwise settle
+/// its unsettled time at the new rate.
+pub fn settle_pools<'info>(
+    economics: &mut Account<TokenEconomics>,
+    pool_accounts: &'info [AccountInfo<'info>],
+    emission_schedule: Option<&Account<EmissionSchedule>>,
+) -> Result<()> {
+    let mut settled = [0u64; 4];
+    for pool_info in pool_accounts {
+        require!(pool_info.is_writable, EconomicsError::InvalidSettlementAccounts);
+
+        // Owner and discriminator are checked on load, the address against the stored bump
+        let mut pool = Account::<StakingPool>::try_from(pool_info)?;
+        let pool_address = Pubkey::create_program_address(
+            &[b"pool", &[pool.pool_id], &[pool.bump]],
+            &crate::ID,
+        ).map_err(|_| error!(EconomicsError::InvalidSettlementAccounts))?;
+        require_keys_eq!(pool_address, pool_info.key(), EconomicsError::InvalidSettlementAccounts);
+
+        let (word, bit) = TokenEconomics::pool_bit(pool.pool_id);
+        require!(settled[word] & bit == 0, EconomicsError::IncompleteSettlement);
+        settled[word] |= bit;
+
+        update_reward_accumulation(economics, &mut pool, emission_schedule)?;
+        pool.exit(&crate::ID)?;
+    }
+    require!(settled == economics.pool_ids, EconomicsError::IncompleteSettlement);
+    Ok(())
+}
+
+/// Moves funded rewards into the committed balance as they accrue
+pub fn commit_rewards(economics: &mut TokenEconomics, amount: u64) -> Result<()> {
+    economics.available_rewards = economics.available_rewards
+        .checked_sub(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.committed_rewards = economics.committed_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Moves a campaign pool's reserved rewards into the committed balance as
+/// they accrue
+pub fn commit_campaign_rewards(economics: &mut TokenEconomics, pool: &mut StakingPool, amount: u64) -> Result<()> {
+    pool.campaign_reserved = pool.campaign_reserved
+        .checked_sub(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.campaign_reserved = economics.campaign_reserved
+        .checked_sub(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.committed_rewards = economics.committed_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Releases committed rewards once they leave `reward_vault`. Saturates because
+/// accounts migrated from before solvency tracking start with nothing committed.
+pub fn release_committed_rewards(economics: &mut TokenEconomics, amount: u64) {
+    economics.committed_rewards = economics.committed_rewards.saturating_sub(amount);
+}
+
+/// Commits rewards a position was owed before solvency tracking, so
+/// `sync_reward_funding` no longer counts them as free funding
+pub fn commit_legacy_rewards(economics: &mut TokenEconomics, amount: u64) -> Result<()> {
+    economics.available_rewards = economics.available_rewards.saturating_sub(amount);
+    economics.committed_rewards = economics.committed_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Returns committed rewards a position gave up to the funded balance
+pub fn forfeit_committed_rewards(economics: &mut TokenEconomics, amount: u64) -> Result<()> {
+    let amount = amount.min(economics.committed_rewards);
+    economics.committed_rewards -= amount;
+    economics.available_rewards = economics.available_rewards
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Returns committed rewards a campaign pool position gave up to the pool's
+/// campaign reserve, which rolls into its next campaign or back on close
+pub fn forfeit_campaign_rewards(economics: &mut TokenEconomics, pool: &mut StakingPool, amount: u64) -> Result<()> {
+    let amount = amount.min(economics.committed_rewards);
+    economics.committed_rewards -= amount;
+    pool.campaign_reserved = pool.campaign_reserved
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.campaign_reserved = economics.campaign_reserved
+        .checked_add(amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Recomputes when the available rewards run out at the base rate in effect at `now`
+pub fn refresh_rewards_funded_until(
+    economics: &mut TokenEconomics,
+    emission_schedule: Option<&EmissionSchedule>,
+    now: i64,
+) -> Result<()> {
+    let rate = calculate_base_emission(economics, emission_schedule, now, now + 1)?;
+    economics.rewards_funded_until = match (economics.available_rewards as u128).checked_div(rate) {
+        Some(seconds) => now.saturating_add(seconds.min(i64::MAX as u128) as i64),
+        None => i64::MAX,
+    };
     Ok(())
 }
 
-/// Calculates pending rewards for a user based on their stake and reward debt
+/// Base rewards emitted between `from` and `to`, before any pool multiplier.
+/// Integrates the active schedule exactly across any number of boundaries.
+pub fn calculate_base_emission(
+    economics: &TokenEconomics,
+    emission_schedule: Option<&EmissionSchedule>,
+    from: i64,
+    to: i64,
+) -> Result<u128> {
+    if to <= from {
+        return Ok(0);
+    }
+
+    match emission_schedule {
+        None => {
+            let emitted = (economics.reward_rate_per_second as u128)
+                .checked_mul((to - from) as u128)
+                .ok_or(EconomicsError::MathOverflow)?;
+            Ok(emitted)
+        }
+        Some(schedule) if schedule.kind == EmissionKind::Epochs => {
+            calculate_epoch_emission(&schedule.epochs, from, to)
+        }
+        Some(schedule) => calculate_decay_emission(schedule, from, to),
+    }
+}
+
+/// Sums each epoch's rate over its overlap with `[from, to)`
+fn calculate_epoch_emission(epochs: &[EmissionEpoch], from: i64, to: i64) -> Result<u128> {
+    let mut emitted: u128 = 0;
+    for (index, epoch) in epochs.iter().enumerate() {
+        let epoch_end = epochs.get(index + 1).map_or(i64::MAX, |next| next.start_time);
+        let start = from.max(epoch.start_time);
+        let end = to.min(epoch_end);
+        if end > start {
+            let epoch_rewards = (epoch.rate_per_second as u128)
+                .checked_mul((end - start) as u128)
+                .ok_or(EconomicsError::MathOverflow)?;
+            emitted = emitted.checked_add(epoch_rewards).ok_or(EconomicsError::MathOverflow)?;
+        }
+    }
+    Ok(emitted)
+}
+
+/// Integrates a step-decaying rate over `[from, to)` as the difference of the
+/// cumulative emission at both ends, so settling an interval in several steps
+/// emits exactly what settling it at once would
+fn calculate_decay_emission(schedule: &EmissionSchedule, from: i64, to: i64) -> Result<u128> {
+    let emitted = cumulative_decay_emission(schedule, to)?
+        .saturating_sub(cumulative_decay_emission(schedule, from)?);
+    Ok(emitted)
+}
+
+/// Emission from `schedule.start_time` up to `time`: the full periods as a
+/// geometric series, the current one pro rata
+fn cumulative_decay_emission(schedule: &EmissionSchedule, time: i64) -> Result<u128> {
+    if time <= schedule.start_time {
+        return Ok(0);
+    }
+
+    let period = schedule.decay_period;
+    let elapsed = time.checked_sub(schedule.start_time).ok_or(EconomicsError::MathOverflow)?;
+    let full_periods = (elapsed / period) as u64;
+    let into_period = elapsed % period;
+
+    let factor = (10000 - schedule.decay_bps as u128) * DECAY_SCALE / 10000;
+    let first_period = (schedule.initial_rate as u128)
+        .checked_mul(period as u128)
+        .ok_or(EconomicsError::MathOverflow)?;
+    let emitted_in_periods = |periods: u64| -> Result<u128> {
+        // first_period * (1 - f^n) / (1 - f), or n * first_period without decay
+        let emitted = if factor == DECAY_SCALE {
+            first_period.checked_mul(periods as u128).ok_or(EconomicsError::MathOverflow)?
+        } else {
+            first_period
+                .checked_mul(DECAY_SCALE - fixed_pow(factor, periods))
+                .ok_or(EconomicsError::MathOverflow)?
+                / (DECAY_SCALE - factor)
+        };
+        Ok(emitted)
+    };
+
+    let emitted = emitted_in_periods(full_periods)?;
+    if into_period == 0 {
+        return Ok(emitted);
+    }
+    let current_period = emitted_in_periods(full_periods + 1)?.saturating_sub(emitted);
+    let partial = current_period
+        .checked_mul(into_period as u128)
+        .ok_or(EconomicsError::MathOverflow)?
+        / period as u128;
+    Ok(emitted.checked_add(partial).ok_or(EconomicsError::MathOverflow)?)
+}
+
+/// `base^exp` for a `DECAY_SCALE` fixed-point base no greater than 1
+fn fixed_pow(mut base: u128, mut exp: u64) -> u128 {
+    let mut result = DECAY_SCALE;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base / DECAY_SCALE;
+        }
+        base = base * base / DECAY_SCALE;
+        exp >>= 1;
+    }
+    result
+}
+
+/// Calculates pending rewards for a user based on their boosted balance and reward debt
 pub fn calculate_pending_rewards(
     user_stake: &Account<UserStake>,
     pool: &Account<StakingPool>,
 ) -> Result<u64> {
-    if user_stake.total_staked == 0 {
+    if user_stake.boosted_balance == 0 {
         return Ok(0);
     }
     
-    let earned = (user_stake.total_staked as u128)
+    let earned = (user_stake.boosted_balance as u128)
         .checked_mul(pool.accumulated_reward_per_token as u128)
         .ok_or(EconomicsError::MathOverflow)?
         .checked_div(1_000_000_000_000)
         .ok_or(EconomicsError::MathOverflow)? as u64;
         
-    let pending = earned.checked_sub(user_stake.reward_debt).unwrap_or(0);
+    let pending = earned.saturating_sub(user_stake.reward_debt);
     Ok(pending)
 }
 
-/// Calculates the reward debt for a given stake amount
+/// Calculates the reward debt for a given boosted balance
 pub fn calculate_reward_debt(
-    stake_amount: u64,
+    boosted_balance: u64,
     pool: &Account<StakingPool>,
 ) -> Result<u64> {
-    let debt = (stake_amount as u128)
+    let debt = (boosted_balance as u128)
         .checked_mul(pool.accumulated_reward_per_token as u128)
         .ok_or(EconomicsError::MathOverflow)?
         .checked_div(1_000_000_000_000)
         .ok_or(EconomicsError::MathOverflow)? as u64;
     Ok(debt)
-}
\ No newline at end of file
+}
+
+/// Moves everything the position earned since its last checkpoint, for the
+/// primary reward and every stream, into its pending balances
+pub fn settle_user_rewards(
+    user_stake: &mut Account<UserStake>,
+    pool: &Account<StakingPool>,
+) -> Result<()> {
+    let pending_rewards = calculate_pending_rewards(user_stake, pool)?;
+    user_stake.pending_rewards = user_stake.pending_rewards
+        .checked_add(pending_rewards)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // Streams added since the position was last touched start with zero debt,
+    // which is exact because the boosted balance has not changed since then
+    let boosted_balance = user_stake.boosted_balance as u128;
+    user_stake.stream_rewards.resize(pool.reward_streams.len(), UserStreamReward::default());
+    for (stream, user_reward) in pool.reward_streams.iter().zip(user_stake.stream_rewards.iter_mut()) {
+        let earned = boosted_balance
+            .checked_mul(stream.accumulated_reward_per_token as u128)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_div(1_000_000_000_000)
+            .ok_or(EconomicsError::MathOverflow)? as u64;
+        user_reward.pending_rewards = user_reward.pending_rewards
+            .checked_add(earned.saturating_sub(user_reward.reward_debt))
+            .ok_or(EconomicsError::MathOverflow)?;
+    }
+    Ok(())
+}
+
+/// Checkpoints the position's reward debts at the current accumulators
+pub fn sync_reward_debts(
+    user_stake: &mut Account<UserStake>,
+    pool: &Account<StakingPool>,
+) -> Result<()> {
+    user_stake.reward_debt = calculate_reward_debt(user_stake.boosted_balance, pool)?;
+
+    let boosted_balance = user_stake.boosted_balance as u128;
+    user_stake.stream_rewards.resize(pool.reward_streams.len(), UserStreamReward::default());
+    for (stream, user_reward) in pool.reward_streams.iter().zip(user_stake.stream_rewards.iter_mut()) {
+        user_reward.reward_debt = boosted_balance
+            .checked_mul(stream.accumulated_reward_per_token as u128)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_div(1_000_000_000_000)
+            .ok_or(EconomicsError::MathOverflow)? as u64;
+    }
+    Ok(())
+}
+
+/// Boost-weighted balance of a position at `now`. Each lot counts its amount plus
+/// a bonus proportional to its remaining lock, so the weight decays back to the
+/// raw amount as the unlock time approaches. `bonus_bps` (NFT and loyalty
+/// bonuses) then scales the whole lock-boosted weight.
+pub fn calculate_boosted_balance(user_stake: &UserStake, bonus_bps: u32, now: i64) -> Result<u64> {
+    let max_lock = UserStake::MAX_LOCK_DURATION as u128;
+    let mut boosted = user_stake.total_staked as u128;
+
+    for lot in user_stake.lots.iter() {
+        let remaining_lock = lot.unlock_time
+            .saturating_sub(now)
+            .clamp(0, UserStake::MAX_LOCK_DURATION) as u128;
+        let lot_boost = (lot.amount as u128)
+            .checked_mul(UserStake::MAX_LOCK_BOOST_BPS as u128)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_mul(remaining_lock)
+            .ok_or(EconomicsError::MathOverflow)?
+            .checked_div(max_lock * 10000)
+            .ok_or(EconomicsError::MathOverflow)?;
+        boosted = boosted.checked_add(lot_boost).ok_or(EconomicsError::MathOverflow)?;
+    }
+
+    boosted = boosted
+        .checked_mul(10000 + bonus_bps as u128)
+        .ok_or(EconomicsError::MathOverflow)?
+        / 10000;
+
+    let boosted = u64::try_from(boosted).map_err(|_| EconomicsError::MathOverflow)?;
+    Ok(boosted)
+}
+
+/// Re-weights a position at `now` and keeps the pool's boosted total in sync.
+/// Pending rewards must be settled before and the reward debt reset after.
+/// Loyalty tiers are reached at the first refresh after their threshold.
+pub fn refresh_boosted_balance(
+    user_stake: &mut UserStake,
+    pool: &mut StakingPool,
+    now: i64,
+) -> Result<()> {
+    // A boost verified against a collection the pool no longer uses is dropped
+    let nft_boost_bps = if user_stake.nft_boost_account == Pubkey::default() {
+        0
+    } else if pool.nft_collection != Pubkey::default() && user_stake.nft_boost_collection == pool.nft_collection {
+        pool.nft_boost_bps
+    } else {
+        user_stake.nft_boost_account = Pubkey::default();
+        user_stake.nft_boost_collection = Pubkey::default();
+        0
+    };
+    let loyalty_bonus_bps = if user_stake.total_staked > 0 {
+        pool.loyalty_bonus_bps(now.saturating_sub(user_stake.first_stake_time))
+    } else {
+        0
+    };
+    let bonus_bps = nft_boost_bps as u32 + loyalty_bonus_bps as u32;
+    let boosted_balance = calculate_boosted_balance(user_stake, bonus_bps, now)?;
+    pool.total_boosted = pool.total_boosted
+        .checked_sub(user_stake.boosted_balance)
+        .ok_or(EconomicsError::MathOverflow)?
+        .checked_add(boosted_balance)
+        .ok_or(EconomicsError::MathOverflow)?;
+    user_stake.boosted_balance = boosted_balance;
+    Ok(())
+}
+
+/// Leading fields of a Metaplex `Metadata` account, up to the collection
+#[derive(AnchorDeserialize)]
+struct MetadataPrefix {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    _seller_fee_basis_points: u16,
+    _creators: Option<Vec<MetadataCreator>>,
+    _primary_sale_happened: bool,
+    _is_mutable: bool,
+    _edition_nonce: Option<u8>,
+    _token_standard: Option<u8>,
+    collection: Option<MetadataCollection>,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCreator {
+    _address: Pubkey,
+    _verified: bool,
+    _share: u8,
+}
+
+#[derive(AnchorDeserialize)]
+struct MetadataCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// `Key::MetadataV1` discriminator of Metaplex metadata accounts
+const METADATA_V1_KEY: u8 = 4;
+
+/// Reads the verified collection from the canonical metadata account of `mint`
+fn verified_collection(metadata: &AccountInfo, mint: &Pubkey) -> Result<Option<Pubkey>> {
+    require_keys_eq!(*metadata.owner, TOKEN_METADATA_PROGRAM_ID, EconomicsError::InvalidNftBoostAccounts);
+    let (metadata_address, _) = Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    );
+    require_keys_eq!(metadata.key(), metadata_address, EconomicsError::InvalidNftBoostAccounts);
+
+    let prefix = MetadataPrefix::deserialize(&mut &metadata.try_borrow_data()?[..])
+        .map_err(|_| error!(EconomicsError::InvalidNftBoostAccounts))?;
+    require!(prefix.key == METADATA_V1_KEY, EconomicsError::InvalidNftBoostAccounts);
+    require_keys_eq!(prefix.mint, *mint, EconomicsError::InvalidNftBoostAccounts);
+
+    Ok(prefix.collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key))
+}
+
+/// Records or drops a position's NFT boost. A token account of the owner
+/// holding an NFT of the pool's verified collection becomes the position's
+/// boost account; the recorded account no longer holding it clears the boost.
+/// The caller re-weights the position afterwards.
+pub fn update_nft_boost(
+    pool: &StakingPool,
+    user_stake: &mut UserStake,
+    nft_token_account: &AccountInfo,
+    nft_metadata: Option<&AccountInfo>,
+) -> Result<()> {
+    require!(pool.nft_collection != Pubkey::default(), EconomicsError::NftBoostDisabled);
+
+    // A closed or emptied account simply no longer holds the NFT
+    let token_account = if *nft_token_account.owner == anchor_spl::token::ID
+        || *nft_token_account.owner == spl_token_2022::ID
+    {
+        TokenAccount::try_deserialize(&mut &nft_token_account.try_borrow_data()?[..]).ok()
+    } else {
+        None
+    };
+    let held_mint = token_account
+        .filter(|token_account| token_account.owner == user_stake.user && token_account.amount > 0)
+        .map(|token_account| token_account.mint);
+
+    let active = match held_mint {
+        Some(mint) => {
+            let nft_metadata = nft_metadata.ok_or(EconomicsError::InvalidNftBoostAccounts)?;
+            require!(
+                verified_collection(nft_metadata, &mint)? == Some(pool.nft_collection),
+                EconomicsError::InvalidNftBoostAccounts
+            );
+            user_stake.nft_boost_account = nft_token_account.key();
+            user_stake.nft_boost_collection = pool.nft_collection;
+            true
+        }
+        None => {
+            // Only the recorded account can show the boost is no longer earned
+            require_keys_eq!(
+                nft_token_account.key(),
+                user_stake.nft_boost_account,
+                EconomicsError::InvalidNftBoostAccounts
+            );
+            user_stake.nft_boost_account = Pubkey::default();
+            user_stake.nft_boost_collection = Pubkey::default();
+            false
+        }
+    };
+
+    emit!(NftBoostChangedEvent {
+        user: user_stake.user,
+        pool_id: user_stake.pool_id,
+        nft_token_account: nft_token_account.key(),
+        active,
+    });
+    Ok(())
+}
+
+/// (Re)activates a position about to receive stake while it holds none,
+/// counting it as an active staker again
+pub fn reactivate_position(
+    economics: &mut TokenEconomics,
+    pool: &mut StakingPool,
+    user_stake: &mut UserStake,
+    now: i64,
+) -> Result<()> {
+    if user_stake.total_staked > 0 {
+        return Ok(());
+    }
+
+    user_stake.first_stake_time = now;
+    pool.active_stakers = pool.active_stakers.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+    economics.active_stakers = economics.active_stakers.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+    Ok(())
+}
+
+/// Records a new deposit lot, merging matured lots first if the position is full
+pub fn add_stake_lot(
+    user_stake: &mut UserStake,
+    amount: u64,
+    deposit_time: i64,
+    unlock_time: i64,
+) -> Result<u32> {
+    if user_stake.lots.len() >= UserStake::MAX_LOTS {
+        merge_matured_lots(user_stake, deposit_time);
+    }
+    require!(user_stake.lots.len() < UserStake::MAX_LOTS, EconomicsError::TooManyStakeLots);
+
+    let lot_id = user_stake.next_lot_id;
+    user_stake.next_lot_id = lot_id.checked_add(1).ok_or(EconomicsError::MathOverflow)?;
+    user_stake.lots.push(StakeLot {
+        lot_id,
+        amount,
+        deposit_time,
+        unlock_time,
+    });
+    Ok(lot_id)
+}
+
+/// Collapses all lots unlocked at `now` into the oldest of them
+fn merge_matured_lots(user_stake: &mut UserStake, now: i64) {
+    let mut merged: Option<StakeLot> = None;
+    user_stake.lots.retain(|lot| {
+        if lot.unlock_time > now {
+            return true;
+        }
+        match merged.as_mut() {
+            Some(first) => first.amount += lot.amount,
+            None => merged = Some(*lot),
+        }
+        false
+    });
+    if let Some(lot) = merged {
+        user_stake.lots.insert(0, lot);
+    }
+}
+
+/// Total amount held in lots whose lock has expired
+pub fn unlocked_amount(user_stake: &UserStake, now: i64) -> Result<u64> {
+    let unlocked = user_stake.lots
+        .iter()
+        .filter(|lot| lot.unlock_time <= now)
+        .try_fold(0u64, |total, lot| total.checked_add(lot.amount))
+        .ok_or(EconomicsError::MathOverflow)?;
+    Ok(unlocked)
+}
+
+/// Releases `amount` from matured lots in FIFO order and reports what was taken
+pub fn release_matured_lots(
+    user_stake: &mut UserStake,
+    amount: u64,
+    now: i64,
+) -> Result<Vec<ConsumedLot>> {
+    require!(unlocked_amount(user_stake, now)? >= amount, EconomicsError::MinimumStakePeriodNotMet);
+
+    let mut remaining = amount;
+    let mut consumed = Vec::new();
+    for lot in user_stake.lots.iter_mut().filter(|lot| lot.unlock_time <= now) {
+        if remaining == 0 {
+            break;
+        }
+        let taken = remaining.min(lot.amount);
+        lot.amount -= taken;
+        remaining -= taken;
+        consumed.push(ConsumedLot { lot_id: lot.lot_id, amount: taken });
+    }
+    user_stake.lots.retain(|lot| lot.amount > 0);
+    Ok(consumed)
+}
+
+/// Releases `amount` using matured lots first and then locked lots in FIFO order,
+/// charging the pool's early-exit penalty on the locked part
+pub fn release_lots_early(
+    user_stake: &mut UserStake,
+    pool: &StakingPool,
+    amount: u64,
+    now: i64,
+) -> Result<(Vec<ConsumedLot>, u64)> {
+    require!(pool.penalty_mode != PenaltyMode::Disabled, EconomicsError::EarlyExitDisabled);
+
+    let matured = amount.min(unlocked_amount(user_stake, now)?);
+    let mut consumed = release_matured_lots(user_stake, matured, now)?;
+
+    let mut remaining = amount - matured;
+    let mut penalty: u128 = 0;
+    for lot in user_stake.lots.iter_mut() {
+        if remaining == 0 {
+            break;
+        }
+        let taken = remaining.min(lot.amount);
+        let mut lot_penalty = (taken as u128)
+            .checked_mul(pool.penalty_bps as u128)
+            .ok_or(EconomicsError::MathOverflow)?
+            / 10_000;
+        if pool.penalty_mode == PenaltyMode::Linear {
+            // Scale by the share of the lock that is still ahead
+            let lock_length = lot.unlock_time.saturating_sub(lot.deposit_time).max(1) as u128;
+            let lock_remaining = lot.unlock_time.saturating_sub(now).max(0) as u128;
+            lot_penalty = lot_penalty
+                .checked_mul(lock_remaining.min(lock_length))
+                .ok_or(EconomicsError::MathOverflow)?
+                / lock_length;
+        }
+        penalty = penalty.checked_add(lot_penalty).ok_or(EconomicsError::MathOverflow)?;
+        lot.amount -= taken;
+        remaining -= taken;
+        consumed.push(ConsumedLot { lot_id: lot.lot_id, amount: taken });
+    }
+    require!(remaining == 0, EconomicsError::InsufficientStake);
+    user_stake.lots.retain(|lot| lot.amount > 0);
+
+    Ok((consumed, penalty as u64))
+}
+
+/// Checks an early-exit penalty configuration against the economics setup
+pub fn validate_penalty_config(
+    economics: &TokenEconomics,
+    penalty_bps: u16,
+    penalty_destination: PenaltyDestination,
+) -> Result<()> {
+    require!(penalty_bps <= 10000, EconomicsError::InvalidPenaltyConfig);
+    // Penalties are paid in the stake token, so only a pool rewarding in the same
+    // token can hand them out through the reward accumulator
+    require!(
+        penalty_destination != PenaltyDestination::Stakers || economics.stake_mint == economics.reward_mint,
+        EconomicsError::InvalidPenaltyConfig
+    );
+    Ok(())
+}
+
+/// Checks a piecewise emission schedule: bounded and sorted by start time
+pub fn validate_emission_epochs(epochs: &[EmissionEpoch]) -> Result<()> {
+    require!(
+        !epochs.is_empty() && epochs.len() <= EmissionSchedule::MAX_EPOCHS,
+        EconomicsError::InvalidEmissionSchedule
+    );
+    require!(
+        epochs.windows(2).all(|pair| pair[0].start_time < pair[1].start_time),
+        EconomicsError::InvalidEmissionSchedule
+    );
+    Ok(())
+}
+
+/// Checks a pool's loyalty tiers: bounded, and longer staking never earns less
+pub fn validate_loyalty_tiers(tiers: &[LoyaltyTier]) -> Result<()> {
+    require!(tiers.len() <= StakingPool::MAX_LOYALTY_TIERS, EconomicsError::InvalidLoyaltyTiers);
+    require!(
+        tiers.iter().all(|tier| tier.min_duration > 0 && tier.bonus_bps <= StakingPool::MAX_LOYALTY_BONUS_BPS),
+        EconomicsError::InvalidLoyaltyTiers
+    );
+    require!(
+        tiers.windows(2).all(|pair| pair[0].min_duration < pair[1].min_duration && pair[0].bonus_bps <= pair[1].bonus_bps),
+        EconomicsError::InvalidLoyaltyTiers
+    );
+    Ok(())
+}
+
+/// Result of taking stake out of a position
+pub struct StakeWithdrawal {
+    /// Stake removed from the position, including any penalty
+    pub amount: u64,
+    /// Early-exit penalty withheld from `amount`
+    pub penalty: u64,
+    pub consumed_lots: Vec<ConsumedLot>,
+}
+
+/// Takes `amount` out of a position after settling its rewards, and removes it
+/// from the pool and global totals. With `early_exit` locked lots may be released
+/// against the pool's penalty and 0 means the whole position; otherwise only
+/// matured lots are released and 0 means everything unlocked. Moving the tokens
+/// is left to the caller.
+pub fn withdraw_stake(
+    economics: &mut Account<TokenEconomics>,
+    pool: &mut Account<StakingPool>,
+    user_stake: &mut Account<UserStake>,
+    emission_schedule: Option<&Account<EmissionSchedule>>,
+    amount: u64,
+    now: i64,
+    early_exit: bool,
+) -> Result<StakeWithdrawal> {
+    require!(user_stake.total_staked > 0, EconomicsError::NoStakeToUnstake);
+
+    // Determine actual unstake amount (0 = everything that can be released)
+    let unstake_amount = match (amount, early_exit) {
+        (0, true) => user_stake.total_staked,
+        (0, false) => unlocked_amount(user_stake, now)?,
+        _ => amount,
+    };
+
+    require!(unstake_amount <= user_stake.total_staked, EconomicsError::InsufficientStake);
+    require!(unstake_amount > 0, EconomicsError::MinimumStakePeriodNotMet);
+
+    // Update reward accumulation and claim pending rewards
+    update_reward_accumulation(economics, pool, emission_schedule)?;
+    settle_user_rewards(user_stake, pool)?;
+
+    // Only lots whose lock has expired can be released without a penalty, oldest first
+    let (consumed_lots, penalty) = if early_exit {
+        release_lots_early(user_stake, pool, unstake_amount, now)?
+    } else {
+        (release_matured_lots(user_stake, unstake_amount, now)?, 0)
+    };
+
+    // Update stake amounts
+    user_stake.total_staked = user_stake.total_staked
+        .checked_sub(unstake_amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    // If fully unstaking, handle active staker count
+    if user_stake.total_staked == 0 {
+        pool.active_stakers = pool.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
+        economics.active_stakers = economics.active_stakers.checked_sub(1).ok_or(EconomicsError::MathOverflow)?;
+    }
+
+    // Re-weight the remaining lots and calculate new reward debt
+    refresh_boosted_balance(user_stake, pool, now)?;
+    sync_reward_debts(user_stake, pool)?;
+
+    // Update pool and global totals
+    pool.total_staked = pool.total_staked
+        .checked_sub(unstake_amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+    economics.total_staked = economics.total_staked
+        .checked_sub(unstake_amount)
+        .ok_or(EconomicsError::MathOverflow)?;
+
+    Ok(StakeWithdrawal {
+        amount: unstake_amount,
+        penalty,
+        consumed_lots,
+    })
+}
+
+/// Grows a program-owned account to `new_len` bytes, topping up rent from `payer`.
+/// The new tail is zero-filled, so appended fields start out as their zero value.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if account.data_len() >= new_len {
+        return Ok(());
+    }
+
+    let rent_minimum = Rent::get()?.minimum_balance(new_len);
+    let top_up = rent_minimum.saturating_sub(account.lamports());
+    if top_up > 0 {
+        let cpi_accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), cpi_accounts), top_up)?;
+    }
+
+    account.resize(new_len)?;
+    Ok(())
+}
+
+/// Rejects Token-2022 mints carrying extensions listed in `UNSUPPORTED_MINT_EXTENSIONS`
+pub fn validate_mint_extensions(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let extensions = mint_state.get_extension_types()?;
+    require!(
+        !extensions.iter().any(|extension| UNSUPPORTED_MINT_EXTENSIONS.contains(extension)),
+        EconomicsError::UnsupportedMintExtension
+    );
+    Ok(())
+}
+
+/// Checks that `signer` is the economics authority or holds `role`
+pub fn require_role(
+    economics: &Account<TokenEconomics>,
+    signer: &Pubkey,
+    role_assignment: Option<&Account<RoleAssignment>>,
+    role: Role,
+) -> Result<()> {
+    if *signer == economics.authority {
+        return Ok(());
+    }
+
+    let role_assignment = role_assignment.ok_or(EconomicsError::Unauthorized)?;
+    require_keys_eq!(role_assignment.economics, economics.key(), EconomicsError::Unauthorized);
+    require_keys_eq!(role_assignment.member, *signer, EconomicsError::Unauthorized);
+    require!(role_assignment.has_role(role), EconomicsError::MissingRole);
+    Ok(())
+}
+
+/// Rejects direct parameter updates while changes must go through the timelock queue
+pub fn require_no_timelock(economics: &TokenEconomics) -> Result<()> {
+    require!(economics.timelock_delay == 0, EconomicsError::TimelockActive);
+    Ok(())
+}
+
+/// Appends the position's current stake to its voting history, filling in
+/// the header when `init_if_needed` has just created the account. History
+/// starts at the first checkpoint, so older stake only counts from then on.
+pub fn record_stake_checkpoint(
+    checkpoints: &mut StakeCheckpoints,
+    user_stake: &Account<UserStake>,
+    bump: u8,
+    now: i64,
+) {
+    if checkpoints.user_stake == Pubkey::default() {
+        checkpoints.user_stake = user_stake.key();
+        checkpoints.bump = bump;
+    }
+    checkpoints.push(now, user_stake.total_staked);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch(start_time: i64, rate_per_second: u64) -> EmissionEpoch {
+        EmissionEpoch { start_time, rate_per_second }
+    }
+
+    fn decay_schedule(initial_rate: u64, start_time: i64, decay_period: i64, decay_bps: u16) -> EmissionSchedule {
+        EmissionSchedule {
+            economics: Pubkey::default(),
+            kind: EmissionKind::Decay,
+            epochs: Vec::new(),
+            initial_rate,
+            start_time,
+            decay_period,
+            decay_bps,
+            bump: 0,
+        }
+    }
+
+    /// Settling `[from, to)` at every split point must emit what one settlement does
+    fn assert_additive(emission: impl Fn(i64, i64) -> u128, from: i64, to: i64) {
+        let whole = emission(from, to);
+        for split in from..=to {
+            assert_eq!(emission(from, split) + emission(split, to), whole, "split at {split}");
+        }
+    }
+
+    #[test]
+    fn epoch_emission_spans_several_epochs() {
+        let epochs = [epoch(100, 10), epoch(200, 5), epoch(300, 0), epoch(400, 7)];
+
+        // Nothing before the first epoch, then 10/s, 5/s, 0/s and 7/s
+        assert_eq!(calculate_epoch_emission(&epochs, 0, 100).unwrap(), 0);
+        assert_eq!(calculate_epoch_emission(&epochs, 50, 450).unwrap(), 1000 + 500 + 350);
+        assert_eq!(calculate_epoch_emission(&epochs, 150, 250).unwrap(), 500 + 250);
+        // The last epoch runs forever
+        assert_eq!(calculate_epoch_emission(&epochs, 1000, 1010).unwrap(), 70);
+    }
+
+    #[test]
+    fn epoch_emission_is_additive() {
+        let epochs = [epoch(100, 10), epoch(200, 5), epoch(300, 0), epoch(400, 7)];
+        assert_additive(|from, to| calculate_epoch_emission(&epochs, from, to).unwrap(), 50, 450);
+    }
+
+    #[test]
+    fn decay_emission_spans_several_periods() {
+        let schedule = decay_schedule(1000, 100, 10, 5000);
+
+        // Halving every 10s: 1000/s, 500/s, 250/s, then half a period at 125/s
+        assert_eq!(calculate_decay_emission(&schedule, 100, 135).unwrap(), 10000 + 5000 + 2500 + 625);
+        assert_eq!(calculate_decay_emission(&schedule, 0, 110).unwrap(), 10000);
+        assert_eq!(calculate_decay_emission(&schedule, 115, 125).unwrap(), 2500 + 1250);
+        assert_eq!(calculate_decay_emission(&schedule, 0, 100).unwrap(), 0);
+    }
+
+    #[test]
+    fn decay_emission_without_decay_is_flat() {
+        let schedule = decay_schedule(1000, 0, 7, 0);
+        assert_eq!(calculate_decay_emission(&schedule, 3, 1003).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn decay_emission_stops_after_full_decay() {
+        let schedule = decay_schedule(1000, 0, 10, 10000);
+        assert_eq!(calculate_decay_emission(&schedule, 0, 1000).unwrap(), 10000);
+    }
+
+    #[test]
+    fn decay_emission_is_additive() {
+        // Rates that do not divide evenly, so per-period truncation would show
+        for (initial_rate, decay_period, decay_bps) in [(1000, 10, 5000), (777, 13, 1234), (3, 7, 9999), (1, 5, 1)] {
+            let schedule = decay_schedule(initial_rate, 100, decay_period, decay_bps);
+            assert_additive(|from, to| calculate_decay_emission(&schedule, from, to).unwrap(), 90, 200);
+        }
+    }
+
+    #[test]
+    fn decay_emission_survives_many_periods() {
+        let schedule = decay_schedule(1_000_000_000, 0, 1, 100);
+        let whole = calculate_decay_emission(&schedule, 0, 10_000_000).unwrap();
+        let split = calculate_decay_emission(&schedule, 0, 5_000_001).unwrap()
+            + calculate_decay_emission(&schedule, 5_000_001, 10_000_000).unwrap();
+        assert_eq!(whole, split);
+        // Converges to initial_rate / 1%
+        assert!(whole <= 100_000_000_000);
+    }
+
+    #[test]
+    fn fixed_pow_is_monotonic() {
+        for decay_bps in [1u128, 1234, 5000, 9999] {
+            let factor = (10000 - decay_bps) * DECAY_SCALE / 10000;
+            let mut previous = fixed_pow(factor, 0);
+            assert_eq!(previous, DECAY_SCALE);
+            for exp in 1..200 {
+                let power = fixed_pow(factor, exp);
+                assert!(power <= previous, "{decay_bps} bps at {exp}");
+                previous = power;
+            }
+        }
+    }
+}
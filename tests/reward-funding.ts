@@ -0,0 +1,156 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Clock, ProgramTestContext, startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { TokenEconomics } from "../target/types/token_economics";
+import {
+  TOKEN_PROGRAM_ID,
+  MINT_SIZE,
+  getAssociatedTokenAddressSync,
+  createInitializeMintInstruction,
+  createAssociatedTokenAccountInstruction,
+  createMintToInstruction,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+// 资金耗尽需要推进链上时间, 因此在 bankrun 中运行
+describe("token-economics (奖励资金)", () => {
+  let context: ProgramTestContext;
+  let provider: BankrunProvider;
+  let program: Program<TokenEconomics>;
+  let authority: anchor.web3.PublicKey;
+  let economicsAddress: anchor.web3.PublicKey;
+  let userStakeAddress: anchor.web3.PublicKey;
+
+  const POOL_ID = 1;
+  const REWARD_RATE = 100;
+  const FUNDING = 1000; // 按 REWARD_RATE 可支付 10 秒
+
+  const createMint = async () => {
+    const mint = anchor.web3.Keypair.generate();
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(MINT_SIZE);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: authority,
+          newAccountPubkey: mint.publicKey,
+          space: MINT_SIZE,
+          lamports,
+          programId: TOKEN_PROGRAM_ID,
+        }),
+        createInitializeMintInstruction(mint.publicKey, 9, authority, null, TOKEN_PROGRAM_ID)
+      ),
+      [mint]
+    );
+    return mint.publicKey;
+  };
+
+  const mintToOwner = async (mint: anchor.web3.PublicKey, amount: number) => {
+    const tokenAccount = getAssociatedTokenAddressSync(mint, authority);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(authority, tokenAccount, authority, mint),
+        createMintToInstruction(mint, tokenAccount, authority, amount)
+      )
+    );
+  };
+
+  const warp = async (seconds: number) => {
+    const clock = await context.banksClient.getClock();
+    context.setClock(
+      new Clock(
+        clock.slot,
+        clock.epochStartTimestamp,
+        clock.epoch,
+        clock.leaderScheduleEpoch,
+        clock.unixTimestamp + BigInt(seconds)
+      )
+    );
+    return Number(clock.unixTimestamp) + seconds;
+  };
+
+  const fundRewards = (amount: number) =>
+    program.methods
+      .fundRewards(new anchor.BN(amount))
+      .accountsPartial({ authority, role: null, emissionSchedule: null, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+  // 每次质押都会先结算池和仓位; 金额各不相同以免交易重复
+  const stake = (amount: number) =>
+    program.methods
+      .stakeTokens(POOL_ID, new anchor.BN(amount), new anchor.BN(0))
+      .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+      .rpc();
+
+  before(async () => {
+    context = await startAnchor("", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<TokenEconomics>(anchor.workspace.TokenEconomics.idl, provider);
+    authority = provider.wallet.publicKey;
+
+    [economicsAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("economics")],
+      program.programId
+    );
+    [userStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([POOL_ID])],
+      program.programId
+    );
+
+    const stakeMint = await createMint();
+    const rewardMint = await createMint();
+    await mintToOwner(stakeMint, 1_000_000);
+    await mintToOwner(rewardMint, 1_000_000);
+
+    await program.methods
+      .initializeEconomics(new anchor.BN(REWARD_RATE), 500, new anchor.BN(1000), new anchor.BN(1_000_000))
+      .accounts({
+        authority,
+        stakeMint,
+        rewardMint,
+        stakeTokenProgram: TOKEN_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await program.methods
+      .createStakingPool(POOL_ID, 100, new anchor.BN(0), new anchor.BN(10_000_000))
+      .accounts({ authority })
+      .rpc();
+  });
+
+  it("初始化时尚无资金", async () => {
+    const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+    assert.equal(economics.availableRewards.toNumber(), 0);
+    assert.equal(economics.committedRewards.toNumber(), 0);
+    assert.equal(economics.rewardsFundedUntil.toString(), economics.createdAt.toString());
+  });
+
+  it("资金耗尽后停止累积", async () => {
+    await fundRewards(FUNDING);
+    await stake(100_000);
+
+    await warp(100);
+    await stake(1000);
+    let userStake = await program.account.userStake.fetch(userStakeAddress);
+    let economics = await program.account.tokenEconomics.fetch(economicsAddress);
+    assert.equal(userStake.pendingRewards.toNumber(), FUNDING);
+    assert.equal(economics.availableRewards.toNumber(), 0);
+    assert.equal(economics.committedRewards.toNumber(), FUNDING);
+
+    await warp(100);
+    await stake(2000);
+    userStake = await program.account.userStake.fetch(userStakeAddress);
+    assert.equal(userStake.pendingRewards.toNumber(), FUNDING);
+  });
+
+  it("重新注资后 rewards_funded_until 向后推移", async () => {
+    const before = await program.account.tokenEconomics.fetch(economicsAddress);
+    const now = await warp(1);
+    await fundRewards(500);
+
+    const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+    assert.equal(economics.availableRewards.toNumber(), 500);
+    assert.equal(economics.rewardsFundedUntil.toNumber(), now + 500 / REWARD_RATE);
+    assert.isTrue(economics.rewardsFundedUntil.gt(before.rewardsFundedUntil));
+  });
+});
@@ -0,0 +1,152 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { TokenEconomics } from "../target/types/token_economics";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  getAssociatedTokenAddressSync,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeNonTransferableMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createAssociatedTokenAccountInstruction,
+  createMintToInstruction,
+  unpackAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+// 经济系统账户是单例 PDA, 每个用例在独立的 bankrun 环境中初始化
+describe("token-economics (Token-2022)", () => {
+  let provider: BankrunProvider;
+  let program: Program<TokenEconomics>;
+  let authority: anchor.web3.PublicKey;
+
+  const POOL_ID = 1;
+  const TRANSFER_FEE_BPS = 100; // 1%
+
+  beforeEach(async () => {
+    const context = await startAnchor("", [], []);
+    provider = new BankrunProvider(context);
+    program = new Program<TokenEconomics>(anchor.workspace.TokenEconomics.idl, provider);
+    authority = provider.wallet.publicKey;
+  });
+
+  const createMint = async (
+    extensions: ExtensionType[],
+    initializeExtensions: (mint: anchor.web3.PublicKey) => anchor.web3.TransactionInstruction[] = () => []
+  ) => {
+    const mint = anchor.web3.Keypair.generate();
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: authority,
+        newAccountPubkey: mint.publicKey,
+        space: getMintLen(extensions),
+        lamports: anchor.web3.LAMPORTS_PER_SOL, // 远高于免租金额
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      ...initializeExtensions(mint.publicKey),
+      createInitializeMintInstruction(mint.publicKey, 9, authority, null, TOKEN_2022_PROGRAM_ID)
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+
+  const initializeEconomics = (stakeMint: anchor.web3.PublicKey, rewardMint: anchor.web3.PublicKey) =>
+    program.methods
+      .initializeEconomics(new anchor.BN(100), 500, new anchor.BN(1000), new anchor.BN(1_000_000))
+      .accounts({
+        authority,
+        stakeMint,
+        rewardMint,
+        stakeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        rewardTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  const tokenBalance = async (address: anchor.web3.PublicKey) => {
+    const info = await provider.connection.getAccountInfo(address);
+    return Number(unpackAccount(address, info, TOKEN_2022_PROGRAM_ID).amount);
+  };
+
+  it("带转账手续费的质押代币只记入实际到账金额", async () => {
+    const stakeMint = await createMint([ExtensionType.TransferFeeConfig], mint => [
+      createInitializeTransferFeeConfigInstruction(
+        mint,
+        authority,
+        authority,
+        TRANSFER_FEE_BPS,
+        BigInt(1_000_000_000),
+        TOKEN_2022_PROGRAM_ID
+      ),
+    ]);
+    const rewardMint = await createMint([]);
+    await initializeEconomics(stakeMint, rewardMint);
+    await program.methods
+      .createStakingPool(POOL_ID, 100, new anchor.BN(0), new anchor.BN(10_000_000))
+      .accounts({ authority })
+      .rpc();
+
+    const userTokenAccount = getAssociatedTokenAddressSync(stakeMint, authority, false, TOKEN_2022_PROGRAM_ID);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(authority, userTokenAccount, authority, stakeMint, TOKEN_2022_PROGRAM_ID),
+        createMintToInstruction(stakeMint, userTokenAccount, authority, 1_000_000, [], TOKEN_2022_PROGRAM_ID)
+      )
+    );
+
+    const amount = 100_000;
+    const netAmount = amount - (amount * TRANSFER_FEE_BPS) / 10000;
+    await program.methods
+      .stakeTokens(POOL_ID, new anchor.BN(amount), new anchor.BN(0))
+      .accounts({ user: authority, tokenProgram: TOKEN_2022_PROGRAM_ID })
+      .rpc();
+
+    const [economicsAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("economics")],
+      program.programId
+    );
+    const [userStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([POOL_ID])],
+      program.programId
+    );
+    const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+    const userStake = await program.account.userStake.fetch(userStakeAddress);
+
+    assert.equal(userStake.totalStaked.toNumber(), netAmount);
+    assert.equal(userStake.lots[0].amount.toNumber(), netAmount);
+    assert.equal(economics.totalStaked.toNumber(), netAmount);
+    assert.equal(await tokenBalance(economics.stakeVault), netAmount);
+    assert.equal(await tokenBalance(userTokenAccount), 1_000_000 - amount);
+  });
+
+  it("拒绝不可转让的代币", async () => {
+    const stakeMint = await createMint([ExtensionType.NonTransferable], mint => [
+      createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID),
+    ]);
+    const rewardMint = await createMint([]);
+
+    try {
+      await initializeEconomics(stakeMint, rewardMint);
+      assert.fail("应该抛出错误");
+    } catch (error) {
+      assert.include(error.toString(), "UnsupportedMintExtension");
+    }
+  });
+
+  it("拒绝带永久委托的代币", async () => {
+    const stakeMint = await createMint([]);
+    const rewardMint = await createMint([ExtensionType.PermanentDelegate], mint => [
+      createInitializePermanentDelegateInstruction(mint, authority, TOKEN_2022_PROGRAM_ID),
+    ]);
+
+    try {
+      await initializeEconomics(stakeMint, rewardMint);
+      assert.fail("应该抛出错误");
+    } catch (error) {
+      assert.include(error.toString(), "UnsupportedMintExtension");
+    }
+  });
+});
@@ -7,7 +7,9 @@ import {
   createMint,
   getAssociatedTokenAddress,
   mintTo,
-  getAccount
+  getAccount,
+  getMint,
+  createAssociatedTokenAccount
 } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -32,6 +34,14 @@ describe("token-economics", () => {
   const MIN_STAKE = new anchor.BN(1000); // 1000 tokens minimum
   const MAX_STAKE = new anchor.BN(1000000); // 1M tokens maximum
 
+  // 奖励率与排放变更须结算所有现存的池, 每个池恰好传入一次
+  const allPools = async () =>
+    (await program.account.stakingPool.all()).map(pool => ({
+      pubkey: pool.publicKey,
+      isWritable: true,
+      isSigner: false,
+    }));
+
   before(async () => {
     console.log("🚀 设置测试环境...");
 
@@ -93,6 +103,8 @@ describe("token-economics", () => {
           authority: authority,
           stakeMint: stakeMint,
           rewardMint: rewardMint,
+          stakeTokenProgram: TOKEN_PROGRAM_ID,
+          rewardTokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
 
@@ -111,6 +123,9 @@ describe("token-economics", () => {
       assert.equal(economics.activeStakers.toNumber(), 0);
       assert.isFalse(economics.isPaused);
       assert.isFalse(economics.emergencyMode);
+      // 尚未注资, 资金覆盖期限即为当前时间
+      assert.equal(economics.availableRewards.toNumber(), 0);
+      assert.equal(economics.rewardsFundedUntil.toString(), economics.createdAt.toString());
 
       console.log("📊 经济系统初始化完成");
       console.log("  - 奖励率:", economics.rewardRatePerSecond.toString(), "代币/秒");
@@ -150,6 +165,7 @@ describe("token-economics", () => {
       assert.equal(pool.totalStaked.toNumber(), 0);
       assert.equal(pool.activeStakers, 0);
       assert.isTrue(pool.isActive);
+      assert.isTrue(pool.lastRewardUpdateTime.toNumber() > 0);
 
       console.log("🏊 质押池创建完成");
       console.log("  - 池ID:", pool.poolId);
@@ -174,7 +190,10 @@ describe("token-economics", () => {
       );
 
       // 给奖励金库铸造奖励代币
-      const rewardVaultAddress = await getAssociatedTokenAddress(rewardMint, economicsAddress, true);
+      const [rewardVaultAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("reward_vault")],
+        program.programId
+      );
       await mintTo(
         provider.connection,
         provider.wallet.payer,
@@ -194,6 +213,17 @@ describe("token-economics", () => {
 
       assert.equal(Number(userStakeBalance.amount), 10000 * 10**9);
       assert.equal(Number(rewardVaultBalance.amount), 1000000 * 10**9);
+
+      // 直接转入金库的代币需要同步后才计入可分配奖励
+      await program.methods
+        .syncRewardFunding()
+        .accountsPartial({ emissionSchedule: null })
+        .rpc();
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.availableRewards.toString(), (1000000 * 10**9).toString());
+      assert.equal(economics.committedRewards.toNumber(), 0);
+      assert.isTrue(economics.rewardsFundedUntil.toNumber() > Date.now() / 1000);
     });
 
     it("用户质押代币", async () => {
@@ -202,9 +232,10 @@ describe("token-economics", () => {
       const stakeAmount = new anchor.BN(5000 * 10**9); // 5000 tokens
 
       const tx = await program.methods
-        .stakeTokens(POOL_ID, stakeAmount)
+        .stakeTokens(POOL_ID, stakeAmount, new anchor.BN(0))
         .accounts({
           user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
 
@@ -247,6 +278,7 @@ describe("token-economics", () => {
         .claimRewards()
         .accounts({
           user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
 
@@ -282,6 +314,7 @@ describe("token-economics", () => {
         .unstakeTokens(unstakeAmount)
         .accounts({
           user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
         .rpc();
 
@@ -353,6 +386,7 @@ describe("token-economics", () => {
         .accounts({
           authority: authority,
         })
+        .remainingAccounts(await allPools())
         .rpc();
 
       console.log("✅ 更新奖励率交易签名:", tx);
@@ -404,6 +438,2004 @@ describe("token-economics", () => {
     });
   });
 
+  describe("⚖️ 池间独立结算", () => {
+    const POOL_A_ID = 9;
+    const POOL_B_ID = 10;
+    let poolAAddress: anchor.web3.PublicKey;
+    let poolBAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [poolAAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([POOL_A_ID])],
+        program.programId
+      );
+      [poolBAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([POOL_B_ID])],
+        program.programId
+      );
+
+      for (const poolId of [POOL_A_ID, POOL_B_ID]) {
+        await program.methods
+          .createStakingPool(poolId, 100, new anchor.BN(0), new anchor.BN(10000000))
+          .accounts({ authority })
+          .rpc();
+        await program.methods
+          .stakeTokens(poolId, new anchor.BN(1000), new anchor.BN(0))
+          .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+          .rpc();
+      }
+    });
+
+    it("一个池的操作不影响另一个池的累计", async () => {
+      const poolBBefore = await program.account.stakingPool.fetch(poolBAddress);
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      // 池 A 中的追加质押会结算池 A
+      await program.methods
+        .stakeTokens(POOL_A_ID, new anchor.BN(1000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+      const poolA = await program.account.stakingPool.fetch(poolAAddress);
+      const poolBAfter = await program.account.stakingPool.fetch(poolBAddress);
+      assert.isTrue(poolA.lastRewardUpdateTime.gt(poolBBefore.lastRewardUpdateTime));
+      assert.equal(poolBAfter.lastRewardUpdateTime.toString(), poolBBefore.lastRewardUpdateTime.toString());
+      assert.equal(
+        poolBAfter.accumulatedRewardPerToken.toString(),
+        poolBBefore.accumulatedRewardPerToken.toString()
+      );
+    });
+
+    it("调整奖励率前按旧费率结算所有池", async () => {
+      const oldRate = (await program.account.tokenEconomics.fetch(economicsAddress)).rewardRatePerSecond;
+      const poolBBefore = await program.account.stakingPool.fetch(poolBAddress);
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      const pools = await allPools();
+      await program.methods
+        .updateRewardRate(oldRate.muln(2))
+        .accounts({ authority })
+        .remainingAccounts(pools)
+        .rpc();
+
+      // 池 B 质押 1000, 每秒每代币累计增加 旧费率 × 1e12 / 1000
+      const poolBAfter = await program.account.stakingPool.fetch(poolBAddress);
+      const elapsed = poolBAfter.lastRewardUpdateTime.sub(poolBBefore.lastRewardUpdateTime);
+      assert.isTrue(elapsed.toNumber() > 0);
+      assert.equal(
+        poolBAfter.accumulatedRewardPerToken.sub(poolBBefore.accumulatedRewardPerToken).toString(),
+        oldRate.mul(elapsed).mul(new anchor.BN(1_000_000_000)).toString()
+      );
+
+      await program.methods
+        .updateRewardRate(oldRate)
+        .accounts({ authority })
+        .remainingAccounts(pools)
+        .rpc();
+    });
+
+    it("遗漏或重复传入的池被拒绝", async () => {
+      const rate = (await program.account.tokenEconomics.fetch(economicsAddress)).rewardRatePerSecond;
+      const pools = await allPools();
+
+      for (const remaining of [pools.slice(1), [...pools, pools[0]]]) {
+        try {
+          await program.methods
+            .updateRewardRate(rate)
+            .accounts({ authority })
+            .remainingAccounts(remaining)
+            .rpc();
+          assert.fail("应该抛出错误");
+        } catch (error) {
+          assert.include(error.toString(), "IncompleteSettlement");
+        }
+      }
+    });
+  });
+
+  describe("🔁 追加质押与重新质押", () => {
+    const TOPUP_POOL_ID = 2;
+    let topUpPoolAddress: anchor.web3.PublicKey;
+    let topUpStakeAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [topUpPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([TOPUP_POOL_ID])],
+        program.programId
+      );
+      [topUpStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([TOPUP_POOL_ID])],
+        program.programId
+      );
+
+      // 无锁定期的池, 便于立即解质押
+      await program.methods
+        .createStakingPool(TOPUP_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("向已有仓位追加质押", async () => {
+      await program.methods
+        .stakeTokens(TOPUP_POOL_ID, new anchor.BN(10000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+      await program.methods
+        .stakeTokens(TOPUP_POOL_ID, new anchor.BN(5000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+      const userStake = await program.account.userStake.fetch(topUpStakeAddress);
+      const pool = await program.account.stakingPool.fetch(topUpPoolAddress);
+
+      assert.equal(userStake.totalStaked.toNumber(), 15000);
+      assert.equal(userStake.lots.length, 2); // 每笔存入独立成批次
+      assert.equal(pool.totalStaked.toNumber(), 15000);
+      assert.equal(pool.activeStakers, 1); // 追加质押不重复计数
+    });
+
+    it("全部解质押后重新质押", async () => {
+      const economicsBefore = await program.account.tokenEconomics.fetch(economicsAddress);
+
+      await program.methods
+        .unstakeTokens(new anchor.BN(0)) // 0 = 全部解质押
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          stakingPool: topUpPoolAddress,
+          userStake: topUpStakeAddress,
+        })
+        .rpc();
+
+      let userStake = await program.account.userStake.fetch(topUpStakeAddress);
+      let pool = await program.account.stakingPool.fetch(topUpPoolAddress);
+      let economics = await program.account.tokenEconomics.fetch(economicsAddress);
+
+      assert.equal(userStake.totalStaked.toNumber(), 0);
+      assert.equal(userStake.lots.length, 0);
+      assert.equal(pool.activeStakers, 0);
+      assert.equal(
+        economics.activeStakers.toNumber(),
+        economicsBefore.activeStakers.toNumber() - 1
+      );
+
+      await program.methods
+        .stakeTokens(TOPUP_POOL_ID, new anchor.BN(2000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+      userStake = await program.account.userStake.fetch(topUpStakeAddress);
+      pool = await program.account.stakingPool.fetch(topUpPoolAddress);
+      economics = await program.account.tokenEconomics.fetch(economicsAddress);
+
+      assert.equal(userStake.user.toString(), authority.toString());
+      assert.equal(userStake.poolId, TOPUP_POOL_ID);
+      assert.equal(userStake.totalStaked.toNumber(), 2000);
+      assert.equal(pool.totalStaked.toNumber(), 2000);
+      assert.equal(pool.activeStakers, 1);
+      assert.equal(
+        economics.activeStakers.toNumber(),
+        economicsBefore.activeStakers.toNumber()
+      );
+    });
+  });
+
+  describe("📑 质押批次", () => {
+    const LOTS_POOL_ID = 11;
+    const LOCK_SECONDS = 2;
+    let lotsPoolAddress: anchor.web3.PublicKey;
+    let lotsStakeAddress: anchor.web3.PublicKey;
+
+    const stake = (amount: number) =>
+      program.methods
+        .stakeTokens(LOTS_POOL_ID, new anchor.BN(amount), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    const unstake = (amount: number) =>
+      program.methods
+        .unstakeTokens(new anchor.BN(amount))
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          stakingPool: lotsPoolAddress,
+          userStake: lotsStakeAddress,
+        })
+        .rpc({ commitment: "confirmed" });
+    const waitForUnlock = () => new Promise(resolve => setTimeout(resolve, (LOCK_SECONDS + 1) * 1000));
+
+    before(async () => {
+      [lotsPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([LOTS_POOL_ID])],
+        program.programId
+      );
+      [lotsStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([LOTS_POOL_ID])],
+        program.programId
+      );
+
+      // 2 秒的最短质押期, 便于等待批次到期
+      await program.methods
+        .createStakingPool(LOTS_POOL_ID, 100, new anchor.BN(LOCK_SECONDS), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("追加质押不会重新锁定已到期的批次", async () => {
+      await stake(1000);
+      const firstLot = (await program.account.userStake.fetch(lotsStakeAddress)).lots[0];
+      await waitForUnlock();
+
+      await stake(2000);
+      let userStake = await program.account.userStake.fetch(lotsStakeAddress);
+      assert.equal(userStake.lots.length, 2);
+      assert.equal(userStake.lots[0].unlockTime.toString(), firstLot.unlockTime.toString());
+      assert.isTrue(userStake.lots[0].unlockTime.lte(userStake.lots[1].depositTime));
+
+      // 到期批次可立即取出, 新批次仍锁定
+      await unstake(1000);
+      try {
+        await unstake(2000);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "MinimumStakePeriodNotMet");
+      }
+
+      userStake = await program.account.userStake.fetch(lotsStakeAddress);
+      assert.equal(userStake.totalStaked.toNumber(), 2000);
+      assert.equal(userStake.lots.length, 1);
+      assert.equal(userStake.lots[0].amount.toNumber(), 2000);
+    });
+
+    it("解质押按先进先出消耗批次并在事件中报告", async () => {
+      await stake(1000);
+      await waitForUnlock();
+      const [olderLot, newerLot] = (await program.account.userStake.fetch(lotsStakeAddress)).lots;
+
+      const signature = await unstake(2500);
+
+      const userStake = await program.account.userStake.fetch(lotsStakeAddress);
+      assert.equal(userStake.lots.length, 1);
+      assert.equal(userStake.lots[0].lotId, newerLot.lotId);
+      assert.equal(userStake.lots[0].amount.toNumber(), 500);
+
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const eventParser = new anchor.EventParser(program.programId, program.coder);
+      const events = [...eventParser.parseLogs(tx.meta.logMessages)];
+      const unstakeEvent = events.find(event => event.name === "unstakeEvent");
+      assert.isDefined(unstakeEvent);
+      assert.equal(unstakeEvent.data.amount.toNumber(), 2500);
+      assert.deepEqual(
+        unstakeEvent.data.consumedLots.map(lot => [lot.lotId, lot.amount.toNumber()]),
+        [[olderLot.lotId, 2000], [newerLot.lotId, 500]]
+      );
+    });
+  });
+
+  describe("🛠️ 质押池管理", () => {
+    const MANAGED_POOL_ID = 2; // 复用追加质押测试中的池
+    let managedPoolAddress: anchor.web3.PublicKey;
+    let managedStakeAddress: anchor.web3.PublicKey;
+
+    before(() => {
+      [managedPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([MANAGED_POOL_ID])],
+        program.programId
+      );
+      [managedStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([MANAGED_POOL_ID])],
+        program.programId
+      );
+    });
+
+    it("更新池参数", async () => {
+      await program.methods
+        .updateStakingPool(MANAGED_POOL_ID, 200, null, new anchor.BN(20000000), null)
+        .accounts({ authority })
+        .rpc();
+
+      const pool = await program.account.stakingPool.fetch(managedPoolAddress);
+      assert.equal(pool.rewardMultiplier, 200);
+      assert.equal(pool.minStakePeriod.toNumber(), 0); // 未传入的参数保持不变
+      assert.equal(pool.maxCapacity.toNumber(), 20000000);
+    });
+
+    it("停用后禁止质押但允许解质押", async () => {
+      await program.methods
+        .deactivateStakingPool(MANAGED_POOL_ID)
+        .accounts({ authority })
+        .rpc();
+
+      try {
+        await program.methods
+          .stakeTokens(MANAGED_POOL_ID, new anchor.BN(1000), new anchor.BN(0))
+          .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "PoolNotActive");
+      }
+
+      await program.methods
+        .unstakeTokens(new anchor.BN(1000))
+        .accountsPartial({
+          user: authority,
+          stakingPool: managedPoolAddress,
+          userStake: managedStakeAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      const userStake = await program.account.userStake.fetch(managedStakeAddress);
+      assert.equal(userStake.totalStaked.toNumber(), 1000);
+    });
+
+    it("重新启用后恢复质押", async () => {
+      await program.methods
+        .reactivateStakingPool(MANAGED_POOL_ID)
+        .accounts({ authority })
+        .rpc();
+
+      const pool = await program.account.stakingPool.fetch(managedPoolAddress);
+      assert.isTrue(pool.isActive);
+    });
+  });
+
+  describe("🧹 关闭账户回收租金", () => {
+    const CLOSE_POOL_ID = 4;
+    let closePoolAddress: anchor.web3.PublicKey;
+    let closeStakeAddress: anchor.web3.PublicKey;
+    let closeCheckpointsAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [closePoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([CLOSE_POOL_ID])],
+        program.programId
+      );
+      [closeStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([CLOSE_POOL_ID])],
+        program.programId
+      );
+      [closeCheckpointsAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("checkpoints"), closeStakeAddress.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(CLOSE_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+      await program.methods
+        .stakeTokens(CLOSE_POOL_ID, new anchor.BN(1000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    });
+
+    it("仍有质押时无法关闭", async () => {
+      try {
+        await program.methods
+          .closeUserStake()
+          .accountsPartial({
+            user: authority,
+            userStake: closeStakeAddress,
+            stakingPool: closePoolAddress,
+            checkpoints: closeCheckpointsAddress,
+            autoCompound: null,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "PositionNotEmpty");
+      }
+
+      try {
+        await program.methods
+          .closeStakingPool(CLOSE_POOL_ID)
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "PoolStillActive");
+      }
+    });
+
+    it("清空后关闭仓位和池", async () => {
+      await program.methods
+        .unstakeTokens(new anchor.BN(0))
+        .accountsPartial({
+          user: authority,
+          stakingPool: closePoolAddress,
+          userStake: closeStakeAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      const userStake = await program.account.userStake.fetch(closeStakeAddress);
+      if (userStake.pendingRewards.toNumber() > 0) {
+        await program.methods
+          .claimRewards()
+          .accountsPartial({
+            user: authority,
+            stakingPool: closePoolAddress,
+            userStake: closeStakeAddress,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+      }
+
+      await program.methods
+        .deactivateStakingPool(CLOSE_POOL_ID)
+        .accounts({ authority })
+        .rpc();
+
+      // 仓位账户仍在时可能还有未领取的奖励
+      try {
+        await program.methods
+          .closeStakingPool(CLOSE_POOL_ID)
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "PoolHasOpenPositions");
+      }
+
+      await program.methods
+        .closeUserStake()
+        .accountsPartial({
+          user: authority,
+          userStake: closeStakeAddress,
+          stakingPool: closePoolAddress,
+          checkpoints: closeCheckpointsAddress,
+          autoCompound: null,
+        })
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(closeStakeAddress));
+      // 质押历史随仓位一起关闭
+      assert.isNull(await provider.connection.getAccountInfo(closeCheckpointsAddress));
+
+      await program.methods
+        .closeStakingPool(CLOSE_POOL_ID)
+        .accounts({ authority })
+        .rpc();
+      assert.isNull(await provider.connection.getAccountInfo(closePoolAddress));
+    });
+  });
+
+  describe("⌛ 解绑冷却期", () => {
+    const UNBONDING_POOL_ID = 5;
+    const UNBONDING_PERIOD = 2;
+    let unbondingPoolAddress: anchor.web3.PublicKey;
+    let unbondingStakeAddress: anchor.web3.PublicKey;
+
+    const ticketAddress = (ticketId: number) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("unbonding"),
+          Buffer.from([UNBONDING_POOL_ID]),
+          new anchor.BN(ticketId).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+
+    const requestUnstake = (amount: number, ticketId: number) =>
+      program.methods
+        .requestUnstake(new anchor.BN(amount))
+        .accountsPartial({
+          user: authority,
+          stakingPool: unbondingPoolAddress,
+          userStake: unbondingStakeAddress,
+          unbondingTicket: ticketAddress(ticketId),
+          emissionSchedule: null,
+        })
+        .rpc();
+
+    before(async () => {
+      [unbondingPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([UNBONDING_POOL_ID])],
+        program.programId
+      );
+      [unbondingStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([UNBONDING_POOL_ID])],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(UNBONDING_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+      await program.methods
+        .updateStakingPool(UNBONDING_POOL_ID, null, null, null, new anchor.BN(UNBONDING_PERIOD))
+        .accounts({ authority })
+        .rpc();
+      await program.methods
+        .stakeTokens(UNBONDING_POOL_ID, new anchor.BN(1000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    });
+
+    it("设置冷却期后不能直接解质押", async () => {
+      try {
+        await program.methods
+          .unstakeTokens(new anchor.BN(1000))
+          .accountsPartial({
+            user: authority,
+            stakingPool: unbondingPoolAddress,
+            userStake: unbondingStakeAddress,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "UnbondingRequired");
+      }
+    });
+
+    it("申请解绑后可取消并重新质押", async () => {
+      await requestUnstake(600, 0);
+
+      let userStake = await program.account.userStake.fetch(unbondingStakeAddress);
+      let pool = await program.account.stakingPool.fetch(unbondingPoolAddress);
+      const ticket = await program.account.unbondingTicket.fetch(ticketAddress(0));
+      assert.equal(userStake.totalStaked.toNumber(), 400);
+      assert.equal(pool.totalUnbonding.toNumber(), 600);
+      assert.equal(ticket.amount.toNumber(), 600);
+
+      try {
+        await program.methods
+          .withdrawUnbonded()
+          .accountsPartial({
+            user: authority,
+            unbondingTicket: ticketAddress(0),
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "UnbondingNotComplete");
+      }
+
+      await program.methods
+        .cancelUnbonding()
+        .accountsPartial({
+          user: authority,
+          unbondingTicket: ticketAddress(0),
+          emissionSchedule: null,
+        })
+        .rpc();
+
+      userStake = await program.account.userStake.fetch(unbondingStakeAddress);
+      pool = await program.account.stakingPool.fetch(unbondingPoolAddress);
+      assert.equal(userStake.totalStaked.toNumber(), 1000);
+      assert.equal(pool.totalUnbonding.toNumber(), 0);
+      assert.isNull(await provider.connection.getAccountInfo(ticketAddress(0)));
+    });
+
+    it("冷却期结束后提取", async () => {
+      await requestUnstake(1000, 1);
+      await new Promise(resolve => setTimeout(resolve, (UNBONDING_PERIOD + 1) * 1000));
+
+      const before = await getAccount(provider.connection, userStakeTokenAccount);
+      await program.methods
+        .withdrawUnbonded()
+        .accountsPartial({
+          user: authority,
+          unbondingTicket: ticketAddress(1),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      const after = await getAccount(provider.connection, userStakeTokenAccount);
+
+      assert.equal(Number(after.amount) - Number(before.amount), 1000);
+      const pool = await program.account.stakingPool.fetch(unbondingPoolAddress);
+      assert.equal(pool.totalUnbonding.toNumber(), 0);
+      assert.equal(pool.activeStakers, 0);
+    });
+  });
+
+  describe("🚪 提前退出罚金", () => {
+    const PENALTY_POOL_ID = 6;
+    const ONE_WEEK = 7 * 24 * 60 * 60;
+    let penaltyPoolAddress: anchor.web3.PublicKey;
+    let penaltyStakeAddress: anchor.web3.PublicKey;
+    let penaltyVaultAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [penaltyPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([PENALTY_POOL_ID])],
+        program.programId
+      );
+      [penaltyStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([PENALTY_POOL_ID])],
+        program.programId
+      );
+      [penaltyVaultAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("penalty_vault")],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(PENALTY_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+      await program.methods
+        .stakeTokens(PENALTY_POOL_ID, new anchor.BN(10000), new anchor.BN(ONE_WEEK))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    });
+
+    const unstakeEarly = (amount: number) =>
+      program.methods
+        .unstakeEarly(new anchor.BN(amount))
+        .accountsPartial({
+          user: authority,
+          stakingPool: penaltyPoolAddress,
+          userStake: penaltyStakeAddress,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          emissionSchedule: null,
+        })
+        .rpc();
+
+    it("未开启时不能提前退出", async () => {
+      try {
+        await unstakeEarly(0);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "EarlyExitDisabled");
+      }
+    });
+
+    it("质押与奖励代币不同时不能分给质押者", async () => {
+      try {
+        await program.methods
+          .setEarlyExitPenalty(PENALTY_POOL_ID, { flat: {} }, 1000, { stakers: {} })
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidPenaltyConfig");
+      }
+    });
+
+    it("固定比例罚金进入国库", async () => {
+      await program.methods
+        .setEarlyExitPenalty(PENALTY_POOL_ID, { flat: {} }, 1000, { treasury: {} }) // 10%
+        .accounts({ authority })
+        .rpc();
+
+      const penaltyVaultInfo = await provider.connection.getAccountInfo(penaltyVaultAddress);
+      const penaltyVaultBefore = penaltyVaultInfo
+        ? Number((await getAccount(provider.connection, penaltyVaultAddress)).amount)
+        : 0;
+      const before = await getAccount(provider.connection, userStakeTokenAccount);
+      await unstakeEarly(0); // 0 = 全部, 包括锁定中的批次
+      const after = await getAccount(provider.connection, userStakeTokenAccount);
+      const penaltyVault = await getAccount(provider.connection, penaltyVaultAddress);
+
+      assert.equal(Number(after.amount) - Number(before.amount), 9000);
+      assert.equal(Number(penaltyVault.amount) - penaltyVaultBefore, 1000);
+
+      const userStake = await program.account.userStake.fetch(penaltyStakeAddress);
+      assert.equal(userStake.totalStaked.toNumber(), 0);
+      assert.equal(userStake.lots.length, 0);
+    });
+  });
+
+  describe("♻️ 复投", () => {
+    const COMPOUND_POOL_ID = 7;
+    let compoundPoolAddress: anchor.web3.PublicKey;
+    let compoundStakeAddress: anchor.web3.PublicKey;
+    let autoCompoundAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [compoundPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([COMPOUND_POOL_ID])],
+        program.programId
+      );
+      [compoundStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([COMPOUND_POOL_ID])],
+        program.programId
+      );
+      [autoCompoundAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("auto_compound"), compoundStakeAddress.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(COMPOUND_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+      await program.methods
+        .stakeTokens(COMPOUND_POOL_ID, new anchor.BN(10000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+    });
+
+    it("质押与奖励代币不同时不能复投", async () => {
+      try {
+        await program.methods
+          .compound()
+          .accountsPartial({
+            caller: authority,
+            stakingPool: compoundPoolAddress,
+            userStake: compoundStakeAddress,
+            autoCompound: null,
+            emissionSchedule: null,
+            feeDistribution: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "CompoundUnavailable");
+      }
+    });
+
+    it("开启与关闭自动复投", async () => {
+      await program.methods
+        .enableAutoCompound()
+        .accountsPartial({ user: authority, userStake: compoundStakeAddress })
+        .rpc();
+
+      const autoCompound = await program.account.autoCompound.fetch(autoCompoundAddress);
+      assert.equal(autoCompound.userStake.toString(), compoundStakeAddress.toString());
+      assert.equal(autoCompound.user.toString(), authority.toString());
+
+      await program.methods
+        .disableAutoCompound()
+        .accountsPartial({ user: authority, autoCompound: autoCompoundAddress })
+        .rpc();
+
+      const closed = await provider.connection.getAccountInfo(autoCompoundAddress);
+      assert.isNull(closed);
+    });
+  });
+
+  describe("📦 批量领取", () => {
+    // 额外创建的池, 与池 6、7 一起覆盖 8 个仓位
+    const BATCH_POOL_IDS = [20, 21, 22, 23, 24, 25];
+
+    const positionOf = (poolId: number) => {
+      const [pool] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([poolId])],
+        program.programId
+      );
+      const [userStake] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([poolId])],
+        program.programId
+      );
+      return { pool, userStake };
+    };
+    const positions = [6, 7].map(positionOf);
+
+    const claimAll = (pairs: { pool: anchor.web3.PublicKey; userStake: anchor.web3.PublicKey }[]) =>
+      program.methods
+        .claimAll()
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          emissionSchedule: null,
+          feeDistribution: null,
+        })
+        .remainingAccounts(
+          pairs.flatMap(({ pool, userStake }) => [
+            { pubkey: pool, isWritable: true, isSigner: false },
+            { pubkey: userStake, isWritable: true, isSigner: false },
+          ])
+        )
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      for (const poolId of BATCH_POOL_IDS) {
+        await program.methods
+          .createStakingPool(poolId, 100, new anchor.BN(0), new anchor.BN(10000000))
+          .accounts({ authority })
+          .rpc();
+        await program.methods
+          .stakeTokens(poolId, new anchor.BN(1000), new anchor.BN(0))
+          .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+          .rpc();
+      }
+    });
+
+    it("重复的仓位被拒绝", async () => {
+      try {
+        await claimAll([positions[1], positions[1]]);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidClaimAllAccounts");
+      }
+    });
+
+    it("一次领取多个池的奖励", async () => {
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      const before = await getAccount(provider.connection, userRewardTokenAccount);
+      await claimAll(positions);
+      const after = await getAccount(provider.connection, userRewardTokenAccount);
+
+      assert.isTrue(Number(after.amount) > Number(before.amount));
+      for (const { userStake } of positions) {
+        const position = await program.account.userStake.fetch(userStake);
+        assert.equal(position.pendingRewards.toNumber(), 0);
+      }
+    });
+
+    it("8 个仓位在默认计算单元上限内完成领取", async () => {
+      const batch = [...positions, ...BATCH_POOL_IDS.map(positionOf)];
+      assert.equal(batch.length, 8);
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      // 未设置计算预算, 交易成功即说明未超过默认的 200,000 计算单元
+      const signature = await claimAll(batch);
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      console.log("📦 8 个仓位批量领取消耗计算单元:", tx.meta.computeUnitsConsumed);
+      assert.isAtMost(tx.meta.computeUnitsConsumed, 200_000);
+
+      for (const { userStake } of batch) {
+        const position = await program.account.userStake.fetch(userStake);
+        assert.equal(position.pendingRewards.toNumber(), 0);
+      }
+    });
+  });
+
+  describe("🌊 多奖励代币", () => {
+    const STREAMS_POOL_ID = 12;
+    let streamsPoolAddress: anchor.web3.PublicKey;
+    let streamsStakeAddress: anchor.web3.PublicKey;
+    // 奖励流 0 资金充足; 奖励流 1 速率很高但只注资 5
+    const streams = [
+      { rate: 10, funding: 1_000_000_000 },
+      { rate: 1000, funding: 5 },
+    ].map(config => ({
+      ...config,
+      mint: null as anchor.web3.PublicKey,
+      vault: null as anchor.web3.PublicKey,
+      userAccount: null as anchor.web3.PublicKey,
+    }));
+
+    before(async () => {
+      [streamsPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([STREAMS_POOL_ID])],
+        program.programId
+      );
+      [streamsStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([STREAMS_POOL_ID])],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(STREAMS_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+
+      for (const [index, stream] of streams.entries()) {
+        stream.mint = await createMint(provider.connection, provider.wallet.payer, authority, null, 6);
+        [stream.vault] = anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("stream_vault"), streamsPoolAddress.toBuffer(), stream.mint.toBuffer()],
+          program.programId
+        );
+        stream.userAccount = await createAssociatedTokenAccount(
+          provider.connection,
+          provider.wallet.payer,
+          stream.mint,
+          authority
+        );
+        await mintTo(provider.connection, provider.wallet.payer, stream.mint, stream.userAccount, authority, stream.funding);
+
+        await program.methods
+          .addRewardStream(STREAMS_POOL_ID, new anchor.BN(stream.rate), authority)
+          .accounts({ authority, streamMint: stream.mint, tokenProgram: TOKEN_PROGRAM_ID })
+          .rpc();
+        await program.methods
+          .fundRewardStream(STREAMS_POOL_ID, index, new anchor.BN(stream.funding))
+          .accounts({
+            funder: authority,
+            streamVault: stream.vault,
+            funderTokenAccount: stream.userAccount,
+            streamMint: stream.mint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+      }
+
+      const pool = await program.account.stakingPool.fetch(streamsPoolAddress);
+      assert.deepEqual(
+        pool.rewardStreams.map(stream => stream.availableRewards.toNumber()),
+        streams.map(stream => stream.funding)
+      );
+    });
+
+    it("一次领取支付所有奖励流, 资金不足的奖励流按已注资金额封顶", async () => {
+      await program.methods
+        .stakeTokens(STREAMS_POOL_ID, new anchor.BN(1000), new anchor.BN(0))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      const before = await Promise.all(streams.map(stream => getAccount(provider.connection, stream.userAccount)));
+      await program.methods
+        .claimRewards()
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          stakingPool: streamsPoolAddress,
+          userStake: streamsStakeAddress,
+          emissionSchedule: null,
+          feeDistribution: null,
+          referrerAccount: null,
+        })
+        .remainingAccounts(
+          streams.flatMap(stream => [
+            { pubkey: stream.vault, isWritable: true, isSigner: false },
+            { pubkey: stream.userAccount, isWritable: true, isSigner: false },
+            { pubkey: stream.mint, isWritable: false, isSigner: false },
+            { pubkey: TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+          ])
+        )
+        .rpc();
+      const after = await Promise.all(streams.map(stream => getAccount(provider.connection, stream.userAccount)));
+
+      assert.isTrue(after[0].amount > before[0].amount);
+      // 唯一的质押者拿到奖励流 1 的全部资金, 不会因资金不足而回滚
+      assert.equal(Number(after[1].amount - before[1].amount), streams[1].funding);
+
+      const pool = await program.account.stakingPool.fetch(streamsPoolAddress);
+      assert.isTrue(pool.rewardStreams[0].availableRewards.toNumber() > 0);
+      assert.equal(pool.rewardStreams[1].availableRewards.toNumber(), 0);
+    });
+  });
+
+  describe("💱 治理费分配", () => {
+    const FEE_POOL_ID = 7;
+    let feeDistributionAddress: anchor.web3.PublicKey;
+    let feePoolAddress: anchor.web3.PublicKey;
+    let feeStakeAddress: anchor.web3.PublicKey;
+
+    before(() => {
+      [feeDistributionAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("fee_distribution")],
+        program.programId
+      );
+      [feePoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([FEE_POOL_ID])],
+        program.programId
+      );
+      [feeStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([FEE_POOL_ID])],
+        program.programId
+      );
+    });
+
+    const claimAll = (feeDistribution: anchor.web3.PublicKey | null) =>
+      program.methods
+        .claimAll()
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          emissionSchedule: null,
+          feeDistribution,
+        })
+        .remainingAccounts([
+          { pubkey: feePoolAddress, isWritable: true, isSigner: false },
+          { pubkey: feeStakeAddress, isWritable: true, isSigner: false },
+        ])
+        .rpc();
+
+    it("权重之和必须为 10000", async () => {
+      try {
+        await program.methods
+          .setFeeDistribution([
+            { destination: { treasury: {} }, bps: 5000 },
+            { destination: { burn: {} }, bps: 4000 },
+          ])
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidFeeDistribution");
+      }
+    });
+
+    it("按配置分配并销毁治理费", async () => {
+      await program.methods
+        .setFeeDistribution([
+          { destination: { treasury: {} }, bps: 5000 },
+          { destination: { burn: {} }, bps: 5000 },
+        ])
+        .accounts({ authority })
+        .rpc();
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.feeDistribution.toString(), feeDistributionAddress.toString());
+
+      // 省略分配账户不能绕过配置
+      try {
+        await claimAll(null);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "FeeDistributionMismatch");
+      }
+
+      await new Promise(resolve => setTimeout(resolve, 2000));
+      const supplyBefore = (await getMint(provider.connection, rewardMint)).supply;
+      await claimAll(feeDistributionAddress);
+      const supplyAfter = (await getMint(provider.connection, rewardMint)).supply;
+      assert.isTrue(supplyAfter < supplyBefore);
+    });
+
+    it("清除配置后治理费全部进入国库", async () => {
+      await program.methods
+        .clearFeeDistribution()
+        .accounts({ authority })
+        .rpc();
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.feeDistribution.toString(), anchor.web3.PublicKey.default.toString());
+    });
+  });
+
+  describe("🎯 奖励活动", () => {
+    const CAMPAIGN_POOL_ID = 8;
+    let campaignPoolAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [campaignPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([CAMPAIGN_POOL_ID])],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(CAMPAIGN_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+    });
+
+    const notifyRewardAmount = (amount: number, duration: number) =>
+      program.methods
+        .notifyRewardAmount(CAMPAIGN_POOL_ID, new anchor.BN(amount), new anchor.BN(duration))
+        .accountsPartial({
+          authority,
+          emissionSchedule: null,
+          role: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    it("活动时长必须为正", async () => {
+      try {
+        await notifyRewardAmount(1000, 0);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidRewardCampaign");
+      }
+    });
+
+    it("按数量/时长设置池奖励率", async () => {
+      const economicsBefore = await program.account.tokenEconomics.fetch(economicsAddress);
+      await notifyRewardAmount(1000, 100);
+
+      const pool = await program.account.stakingPool.fetch(campaignPoolAddress);
+      assert.equal(pool.campaignRatePerSecond.toNumber(), 10);
+      assert.isTrue(pool.campaignEndTime.toNumber() > Date.now() / 1000);
+
+      // 活动资金只留给该池, 不计入其他池共享的可分配奖励
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(pool.campaignReserved.toNumber(), 1000);
+      assert.equal(
+        economics.campaignReserved.sub(economicsBefore.campaignReserved).toNumber(),
+        1000
+      );
+      assert.isTrue(economics.availableRewards.lte(economicsBefore.availableRewards));
+    });
+
+    it("未发放的余额滚入新活动", async () => {
+      const before = await program.account.stakingPool.fetch(campaignPoolAddress);
+      await notifyRewardAmount(0, 200);
+      const after = await program.account.stakingPool.fetch(campaignPoolAddress);
+
+      // 剩余约 1000 在 200 秒内发放
+      assert.isTrue(after.campaignRatePerSecond.toNumber() > 0);
+      assert.isTrue(after.campaignRatePerSecond.toNumber() <= 5);
+      assert.isTrue(after.campaignEndTime.toNumber() > before.campaignEndTime.toNumber());
+    });
+  });
+
+  describe("🏦 国库支出", () => {
+    const EPOCH_DURATION = 24 * 60 * 60;
+    const EPOCH_CAP = 10;
+    const PENALTY_EPOCH_CAP = 500; // 提前退出罚金测试已向罚金金库转入 1000
+
+    const withdrawTreasury = (amount: number) =>
+      program.methods
+        .withdrawTreasury(new anchor.BN(amount))
+        .accountsPartial({
+          authority,
+          role: null,
+          recipient: userRewardTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      await program.methods
+        .setTreasuryLimits(new anchor.BN(EPOCH_DURATION), new anchor.BN(EPOCH_CAP), new anchor.BN(PENALTY_EPOCH_CAP))
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("拒绝转入白名单之外的账户", async () => {
+      try {
+        await withdrawTreasury(1);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "RecipientNotAllowed");
+      }
+    });
+
+    it("向白名单账户支出", async () => {
+      await program.methods
+        .addTreasuryRecipient(userRewardTokenAccount)
+        .accounts({ authority })
+        .rpc();
+
+      const before = await getAccount(provider.connection, userRewardTokenAccount);
+      await withdrawTreasury(1);
+      const after = await getAccount(provider.connection, userRewardTokenAccount);
+      assert.equal(Number(after.amount - before.amount), 1);
+
+      const [treasuryConfig] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("treasury_config")],
+        program.programId
+      );
+      const config = await program.account.treasuryConfig.fetch(treasuryConfig);
+      assert.equal(config.spentThisEpoch.toNumber(), 1);
+    });
+
+    it("超过周期上限时拒绝支出", async () => {
+      try {
+        await withdrawTreasury(EPOCH_CAP);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "TreasuryCapExceeded");
+      }
+    });
+
+    it("拒绝数量为 0 的支出", async () => {
+      try {
+        await withdrawTreasury(0);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidWithdrawalAmount");
+      }
+    });
+
+    it("提取提前退出罚金", async () => {
+      const [penaltyVaultAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("penalty_vault")],
+        program.programId
+      );
+      const withdrawPenalties = (amount: number) =>
+        program.methods
+          .withdrawPenalties(new anchor.BN(amount))
+          .accountsPartial({
+            authority,
+            role: null,
+            recipient: userStakeTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+
+      await program.methods
+        .addTreasuryRecipient(userStakeTokenAccount)
+        .accounts({ authority })
+        .rpc();
+
+      const vaultBefore = await getAccount(provider.connection, penaltyVaultAddress);
+      const before = await getAccount(provider.connection, userStakeTokenAccount);
+      await withdrawPenalties(PENALTY_EPOCH_CAP);
+      const vaultAfter = await getAccount(provider.connection, penaltyVaultAddress);
+      const after = await getAccount(provider.connection, userStakeTokenAccount);
+
+      assert.equal(Number(after.amount - before.amount), PENALTY_EPOCH_CAP);
+      assert.equal(Number(vaultBefore.amount - vaultAfter.amount), PENALTY_EPOCH_CAP);
+
+      // 罚金上限独立于治理费支出上限
+      try {
+        await withdrawPenalties(1);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "TreasuryCapExceeded");
+      }
+    });
+  });
+
+  describe("🤝 推荐奖励", () => {
+    const REFERRAL_POOL_ID = 7;
+    const REFERRAL_SHARE = 5000;
+    const referrer = anchor.web3.Keypair.generate();
+    let referrerAccountAddress: anchor.web3.PublicKey;
+    let ownReferrerAccountAddress: anchor.web3.PublicKey;
+    let referralAddress: anchor.web3.PublicKey;
+
+    const referrerAccountOf = (wallet: anchor.web3.PublicKey) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("referrer"), wallet.toBuffer()],
+        program.programId
+      )[0];
+
+    const stakeWithReferrer = (referrerAccount: anchor.web3.PublicKey) =>
+      program.methods
+        .stakeTokens(REFERRAL_POOL_ID, new anchor.BN(1000), new anchor.BN(0))
+        .accountsPartial({
+          user: authority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          emissionSchedule: null,
+          referrerAccount,
+          referral: referralAddress,
+        })
+        .rpc();
+
+    before(async () => {
+      referrerAccountAddress = referrerAccountOf(referrer.publicKey);
+      ownReferrerAccountAddress = referrerAccountOf(authority);
+      [referralAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("referral"), authority.toBuffer()],
+        program.programId
+      );
+
+      const airdrop = await provider.connection.requestAirdrop(
+        referrer.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      await program.methods
+        .registerReferrer()
+        .accounts({ referrer: referrer.publicKey })
+        .signers([referrer])
+        .rpc();
+      await program.methods
+        .registerReferrer()
+        .accounts({ referrer: authority })
+        .rpc();
+      await program.methods
+        .setReferralShare(REFERRAL_SHARE)
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("不能推荐自己", async () => {
+      try {
+        await stakeWithReferrer(ownReferrerAccountAddress);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "SelfReferral");
+      }
+    });
+
+    it("质押时绑定推荐人", async () => {
+      await stakeWithReferrer(referrerAccountAddress);
+
+      const referral = await program.account.referral.fetch(referralAddress);
+      assert.equal(referral.user.toString(), authority.toString());
+      assert.equal(referral.referrer.toString(), referrer.publicKey.toString());
+      const referrerAccount = await program.account.referrerAccount.fetch(referrerAccountAddress);
+      assert.equal(referrerAccount.refereeCount.toNumber(), 1);
+    });
+
+    it("推荐人从治理费中获得分成并单独领取", async () => {
+      const [pool] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([REFERRAL_POOL_ID])],
+        program.programId
+      );
+      const [userStake] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([REFERRAL_POOL_ID])],
+        program.programId
+      );
+      await new Promise(resolve => setTimeout(resolve, 2000));
+
+      const claimAll = (referrerAccount: anchor.web3.PublicKey | null) =>
+        program.methods
+          .claimAll()
+          .accountsPartial({
+            user: authority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            emissionSchedule: null,
+            feeDistribution: null,
+            referrerAccount,
+          })
+          .remainingAccounts([
+            { pubkey: pool, isWritable: true, isSigner: false },
+            { pubkey: userStake, isWritable: true, isSigner: false },
+          ])
+          .rpc();
+
+      // 被推荐用户领取时必须带上推荐人账户
+      try {
+        await claimAll(null);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidReferralAccounts");
+      }
+      await claimAll(referrerAccountAddress);
+
+      const { pendingRewards } = await program.account.referrerAccount.fetch(referrerAccountAddress);
+      assert.isTrue(pendingRewards.toNumber() > 0);
+
+      const referrerRewardAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        provider.wallet.payer,
+        rewardMint,
+        referrer.publicKey
+      );
+      await program.methods
+        .claimReferralRewards()
+        .accounts({ referrer: referrer.publicKey, tokenProgram: TOKEN_PROGRAM_ID })
+        .signers([referrer])
+        .rpc();
+
+      const received = await getAccount(provider.connection, referrerRewardAccount);
+      assert.equal(Number(received.amount), pendingRewards.toNumber());
+      const claimed = await program.account.referrerAccount.fetch(referrerAccountAddress);
+      assert.equal(claimed.pendingRewards.toNumber(), 0);
+    });
+  });
+
+  describe("🗳️ 治理提案", () => {
+    const VOTING_PERIOD = 2;
+    const EXECUTION_GRACE_PERIOD = 5;
+    const VOTER_POOL_ID = 7;
+    let governanceAuthority: anchor.web3.PublicKey;
+    let governanceRoleAddress: anchor.web3.PublicKey;
+    let voterStakeAddress: anchor.web3.PublicKey;
+
+    const proposalAddress = (proposalId: anchor.BN) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("proposal"), proposalId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    before(async () => {
+      [governanceAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("governance_authority")],
+        program.programId
+      );
+      [governanceRoleAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("role"), governanceAuthority.toBuffer()],
+        program.programId
+      );
+      [voterStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([VOTER_POOL_ID])],
+        program.programId
+      );
+
+      await program.methods
+        .initializeGovernance(new anchor.BN(VOTING_PERIOD), 1, new anchor.BN(0), new anchor.BN(EXECUTION_GRACE_PERIOD))
+        .accounts({ authority })
+        .rpc();
+      // 治理签名者需要对应角色才能排队奖励率变更
+      await program.methods
+        .grantRole(governanceAuthority, { rateManager: {} })
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("质押者投票通过后执行提案", async () => {
+      const governance = await program.account.governanceConfig.fetch(
+        anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("governance")], program.programId)[0]
+      );
+      const proposalId = governance.nextProposalId;
+      const proposal = proposalAddress(proposalId);
+
+      await program.methods
+        .createProposal({ parameter: { change: { rewardRate: { newRate: REWARD_RATE } } } })
+        .accountsPartial({ proposer: authority, userStake: voterStakeAddress })
+        .rpc();
+
+      await program.methods
+        .castVote(proposalId, true)
+        .accountsPartial({ voter: authority, proposal, userStake: voterStakeAddress })
+        .rpc();
+
+      // 同一仓位不能重复投票
+      try {
+        await program.methods
+          .castVote(proposalId, true)
+          .accountsPartial({ voter: authority, proposal, userStake: voterStakeAddress })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "already in use");
+      }
+
+      const tally = await program.account.proposal.fetch(proposal);
+      assert.equal(tally.forVotes.toNumber(), 10000);
+
+      // 投票期内投票记录用于防止重复投票, 不能关闭
+      const [voteRecord] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vote"), proposal.toBuffer(), voterStakeAddress.toBuffer()],
+        program.programId
+      );
+      const closeVoteRecord = () =>
+        program.methods
+          .closeVoteRecord(proposalId)
+          .accountsPartial({ voter: authority, proposal, voteRecord })
+          .rpc();
+      try {
+        await closeVoteRecord();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "VotingStillOpen");
+      }
+
+      await new Promise(resolve => setTimeout(resolve, (VOTING_PERIOD + 1) * 1000));
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      const [pendingChange] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_change"), economics.nextChangeId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .executeProposal(proposalId)
+        .accountsPartial({
+          executor: authority,
+          proposal,
+          role: governanceRoleAddress,
+          pendingChange,
+          treasuryConfig: null,
+          treasuryVault: null,
+          recipient: null,
+          rewardMint: null,
+          tokenProgram: null,
+        })
+        .rpc();
+
+      const executed = await program.account.proposal.fetch(proposal);
+      assert.isTrue(executed.executed);
+      const queued = await program.account.pendingChange.fetch(pendingChange);
+      assert.equal(queued.proposer.toString(), governanceAuthority.toString());
+
+      await closeVoteRecord();
+      assert.isNull(await provider.connection.getAccountInfo(voteRecord));
+    });
+
+    it("按历史检查点查询投票权", async () => {
+      const [checkpoints] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("checkpoints"), voterStakeAddress.toBuffer()],
+        program.programId
+      );
+      const now = Math.floor(Date.now() / 1000) + 1;
+
+      const current = await program.methods
+        .votingPowerAt(new anchor.BN(now))
+        .accounts({ checkpoints })
+        .view();
+      const beforeStaking = await program.methods
+        .votingPowerAt(new anchor.BN(0))
+        .accounts({ checkpoints })
+        .view();
+
+      assert.equal(current.toNumber(), 10000);
+      assert.equal(beforeStaking.toNumber(), 0);
+
+      // 已有历史的仓位不能再由他人追加检查点
+      try {
+        await program.methods
+          .checkpointStake()
+          .accountsPartial({ userStake: voterStakeAddress, checkpoints, payer: authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "CheckpointHistoryExists");
+      }
+    });
+
+    it("没有投票的提案无法执行", async () => {
+      const governance = await program.account.governanceConfig.fetch(
+        anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("governance")], program.programId)[0]
+      );
+      const proposalId = governance.nextProposalId;
+      const proposal = proposalAddress(proposalId);
+
+      await program.methods
+        .createProposal({ acceptAuthority: {} })
+        .accountsPartial({ proposer: authority, userStake: voterStakeAddress })
+        .rpc();
+      await new Promise(resolve => setTimeout(resolve, (VOTING_PERIOD + 1) * 1000));
+
+      try {
+        await program.methods
+          .executeProposal(proposalId)
+          .accountsPartial({
+            executor: authority,
+            proposal,
+            role: null,
+            pendingChange: null,
+            treasuryConfig: null,
+            treasuryVault: null,
+            recipient: null,
+            rewardMint: null,
+            tokenProgram: null,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "ProposalNotPassed");
+      }
+    });
+
+    it("超过执行宽限期的提案过期", async () => {
+      const governance = await program.account.governanceConfig.fetch(
+        anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("governance")], program.programId)[0]
+      );
+      const proposalId = governance.nextProposalId;
+      const proposal = proposalAddress(proposalId);
+
+      await program.methods
+        .createProposal({ acceptAuthority: {} })
+        .accountsPartial({ proposer: authority, userStake: voterStakeAddress })
+        .rpc();
+      await program.methods
+        .castVote(proposalId, true)
+        .accountsPartial({ voter: authority, proposal, userStake: voterStakeAddress })
+        .rpc();
+      await new Promise(resolve => setTimeout(resolve, (VOTING_PERIOD + EXECUTION_GRACE_PERIOD + 1) * 1000));
+
+      try {
+        await program.methods
+          .executeProposal(proposalId)
+          .accountsPartial({
+            executor: authority,
+            proposal,
+            role: null,
+            pendingChange: null,
+            treasuryConfig: null,
+            treasuryVault: null,
+            recipient: null,
+            rewardMint: null,
+            tokenProgram: null,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "ProposalExpired");
+      }
+    });
+  });
+
+  describe("🔒 锁仓加成", () => {
+    const LOCK_POOL_ID = 3;
+    const FOUR_YEARS = 4 * 365 * 24 * 60 * 60;
+    let lockPoolAddress: anchor.web3.PublicKey;
+    let lockStakeAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [lockPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([LOCK_POOL_ID])],
+        program.programId
+      );
+      [lockStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([LOCK_POOL_ID])],
+        program.programId
+      );
+
+      await program.methods
+        .createStakingPool(LOCK_POOL_ID, 100, new anchor.BN(0), new anchor.BN(10000000))
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("锁定4年获得最大加成", async () => {
+      await program.methods
+        .stakeTokens(LOCK_POOL_ID, new anchor.BN(10000), new anchor.BN(FOUR_YEARS))
+        .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+        .rpc();
+
+      const userStake = await program.account.userStake.fetch(lockStakeAddress);
+      const pool = await program.account.stakingPool.fetch(lockPoolAddress);
+
+      // 10000 * (1 + 1.5 * 剩余锁定/4年) ≈ 25000
+      assert.isTrue(userStake.boostedBalance.toNumber() > 24990);
+      assert.equal(pool.totalBoosted.toString(), userStake.boostedBalance.toString());
+    });
+
+    it("锁定期内无法解质押", async () => {
+      try {
+        await program.methods
+          .unstakeTokens(new anchor.BN(10000))
+          .accountsPartial({
+            user: authority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            stakingPool: lockPoolAddress,
+            userStake: lockStakeAddress,
+          })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "MinimumStakePeriodNotMet");
+      }
+    });
+
+    it("无效的锁定时长被拒绝", async () => {
+      try {
+        await program.methods
+          .stakeTokens(LOCK_POOL_ID, new anchor.BN(10000), new anchor.BN(60))
+          .accounts({ user: authority, tokenProgram: TOKEN_PROGRAM_ID })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidLockDuration");
+      }
+    });
+  });
+
+  describe("🖼️ NFT 持有加成", () => {
+    const NFT_POOL_ID = 3;
+    const collection = anchor.web3.Keypair.generate().publicKey;
+    let nftPoolAddress: anchor.web3.PublicKey;
+    let nftStakeAddress: anchor.web3.PublicKey;
+
+    before(() => {
+      [nftPoolAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pool"), Buffer.from([NFT_POOL_ID])],
+        program.programId
+      );
+      [nftStakeAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stake"), authority.toBuffer(), Buffer.from([NFT_POOL_ID])],
+        program.programId
+      );
+    });
+
+    const setNftBoost = (collectionKey: anchor.web3.PublicKey, boostBps: number) =>
+      program.methods
+        .setNftBoost(NFT_POOL_ID, collectionKey, boostBps)
+        .accounts({ authority })
+        .rpc();
+
+    const refreshWithNft = (nftTokenAccount: anchor.web3.PublicKey, nftMetadata: anchor.web3.PublicKey | null) =>
+      program.methods
+        .refreshBoost()
+        .accountsPartial({
+          stakingPool: nftPoolAddress,
+          userStake: nftStakeAddress,
+          emissionSchedule: null,
+          nftTokenAccount,
+          nftMetadata,
+        })
+        .rpc();
+
+    it("未开启 NFT 加成的池拒绝 NFT 账户", async () => {
+      try {
+        await refreshWithNft(userRewardTokenAccount, null);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "NftBoostDisabled");
+      }
+    });
+
+    it("加成上限为 10000 基点", async () => {
+      try {
+        await setNftBoost(collection, 10001);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidNftBoostConfig");
+      }
+    });
+
+    it("非该系列的 NFT 不能获得加成", async () => {
+      await setNftBoost(collection, 5000);
+      const pool = await program.account.stakingPool.fetch(nftPoolAddress);
+      assert.equal(pool.nftCollection.toString(), collection.toString());
+      assert.equal(pool.nftBoostBps, 5000);
+
+      // 奖励代币账户不对应任何 Metaplex 元数据
+      try {
+        await refreshWithNft(userRewardTokenAccount, economicsAddress);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidNftBoostAccounts");
+      }
+
+      const userStake = await program.account.userStake.fetch(nftStakeAddress);
+      assert.equal(userStake.nftBoostAccount.toString(), anchor.web3.PublicKey.default.toString());
+
+      await setNftBoost(anchor.web3.PublicKey.default, 0);
+    });
+  });
+
+  describe("🏅 忠诚度加成", () => {
+    const setLoyaltyTiers = (tiers: { minDuration: anchor.BN; bonusBps: number }[]) =>
+      program.methods
+        .setLoyaltyTiers(POOL_ID, tiers)
+        .accounts({ authority })
+        .rpc();
+
+    const refreshBoost = () =>
+      program.methods
+        .refreshBoost()
+        .accountsPartial({
+          stakingPool: stakingPoolAddress,
+          userStake: userStakeAddress,
+          emissionSchedule: null,
+          nftTokenAccount: null,
+          nftMetadata: null,
+        })
+        .rpc();
+
+    it("等级门槛必须递增", async () => {
+      try {
+        await setLoyaltyTiers([
+          { minDuration: new anchor.BN(180 * 24 * 60 * 60), bonusBps: 2500 },
+          { minDuration: new anchor.BN(30 * 24 * 60 * 60), bonusBps: 1000 },
+        ]);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidLoyaltyTiers");
+      }
+    });
+
+    it("持续质押达到门槛后提高权重", async () => {
+      await refreshBoost();
+      const before = await program.account.userStake.fetch(userStakeAddress);
+
+      // 仓位早已超过 1 秒门槛, 达到 +10% 一档
+      await setLoyaltyTiers([
+        { minDuration: new anchor.BN(1), bonusBps: 1000 },
+        { minDuration: new anchor.BN(180 * 24 * 60 * 60), bonusBps: 2500 },
+      ]);
+      await refreshBoost();
+      const after = await program.account.userStake.fetch(userStakeAddress);
+      const pool = await program.account.stakingPool.fetch(stakingPoolAddress);
+
+      assert.equal(pool.loyaltyTiers.length, 2);
+      assert.isTrue(after.boostedBalance.toNumber() > before.boostedBalance.toNumber());
+      assert.isTrue(after.boostedBalance.toNumber() >= after.totalStaked.toNumber() * 1.1);
+
+      await setLoyaltyTiers([]);
+      await refreshBoost();
+    });
+  });
+
+  describe("🔑 管理员权限转移", () => {
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    it("提名后可以取消", async () => {
+      await program.methods
+        .proposeAuthority(newAuthority.publicKey)
+        .accounts({ authority })
+        .rpc();
+
+      let economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.pendingAuthority.toString(), newAuthority.publicKey.toString());
+
+      await program.methods
+        .cancelAuthorityTransfer()
+        .accounts({ authority })
+        .rpc();
+
+      economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.pendingAuthority.toString(), anchor.web3.PublicKey.default.toString());
+      assert.equal(economics.authority.toString(), authority.toString());
+    });
+
+    it("只有被提名者可以接受", async () => {
+      await program.methods
+        .proposeAuthority(newAuthority.publicKey)
+        .accounts({ authority })
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({ newAuthority: authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidPendingAuthority");
+      }
+    });
+
+    it("两步完成权限转移并转回", async () => {
+      await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: newAuthority.publicKey })
+        .signers([newAuthority])
+        .rpc();
+
+      let economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.authority.toString(), newAuthority.publicKey.toString());
+      assert.equal(economics.pendingAuthority.toString(), anchor.web3.PublicKey.default.toString());
+
+      // 转回原管理员, 以便后续测试继续使用
+      await program.methods
+        .proposeAuthority(authority)
+        .accounts({ authority: newAuthority.publicKey })
+        .signers([newAuthority])
+        .rpc();
+      await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: authority })
+        .rpc();
+
+      economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.authority.toString(), authority.toString());
+    });
+  });
+
+  describe("🛡️ 角色权限", () => {
+    const guardian = anchor.web3.Keypair.generate();
+    let guardianRoleAddress: anchor.web3.PublicKey;
+
+    before(async () => {
+      [guardianRoleAddress] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("role"), guardian.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .grantRole(guardian.publicKey, { pauser: {} })
+        .accounts({ authority })
+        .rpc();
+    });
+
+    it("暂停角色可以暂停和恢复系统", async () => {
+      await program.methods
+        .setPauseState(true)
+        .accounts({ authority: guardian.publicKey, role: guardianRoleAddress })
+        .signers([guardian])
+        .rpc();
+
+      let economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.isTrue(economics.isPaused);
+
+      await program.methods
+        .setPauseState(false)
+        .accounts({ authority: guardian.publicKey, role: guardianRoleAddress })
+        .signers([guardian])
+        .rpc();
+
+      economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.isFalse(economics.isPaused);
+    });
+
+    it("暂停角色不能修改奖励率", async () => {
+      try {
+        await program.methods
+          .updateRewardRate(new anchor.BN(1))
+          .accounts({ authority: guardian.publicKey, role: guardianRoleAddress })
+          .signers([guardian])
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "MissingRole");
+      }
+    });
+
+    it("撤销最后一个角色后关闭账户", async () => {
+      await program.methods
+        .revokeRole(guardian.publicKey, { pauser: {} })
+        .accounts({ authority })
+        .rpc();
+
+      const roleAccount = await provider.connection.getAccountInfo(guardianRoleAddress);
+      assert.isNull(roleAccount);
+    });
+  });
+
+  describe("⏳ 参数变更时间锁", () => {
+    const DELAY = 2;
+
+    const queueChange = async (change: any) => {
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      const changeId = economics.nextChangeId;
+      const [pendingChange] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("pending_change"), changeId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      await program.methods
+        .queueParameterChange(change)
+        .accountsPartial({ authority, pendingChange, role: null })
+        .rpc();
+      return changeId;
+    };
+
+    const executeChange = async (changeId: anchor.BN) =>
+      program.methods
+        .executeParameterChange(changeId)
+        .accountsPartial({ proposer: authority, stakingPool: null, emissionSchedule: null })
+        .remainingAccounts(await allPools())
+        .rpc();
+
+    it("开启时间锁", async () => {
+      // 延迟为 0 时排队的变更可立即执行
+      const changeId = await queueChange({ timelockDelay: { delay: new anchor.BN(DELAY) } });
+      await executeChange(changeId);
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.timelockDelay.toNumber(), DELAY);
+    });
+
+    it("时间锁开启后禁止直接修改", async () => {
+      try {
+        await program.methods
+          .updateRewardRate(new anchor.BN(1))
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockActive");
+      }
+    });
+
+    it("延迟到期前无法执行, 到期后任何人可执行", async () => {
+      const before = await program.account.tokenEconomics.fetch(economicsAddress);
+      const newRate = before.rewardRatePerSecond.addn(1);
+      const changeId = await queueChange({ rewardRate: { newRate } });
+
+      try {
+        await executeChange(changeId);
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockNotExpired");
+      }
+
+      await new Promise(resolve => setTimeout(resolve, (DELAY + 1) * 1000));
+      await executeChange(changeId);
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.rewardRatePerSecond.toString(), newRate.toString());
+    });
+
+    it("排放计划与 NFT 加成同样须经过队列", async () => {
+      try {
+        await program.methods
+          .setEmissionDecay(new anchor.BN(100), new anchor.BN(0), new anchor.BN(3600), 5000)
+          .accounts({ authority })
+          .rpc();
+        assert.fail("应该抛出错误");
+      } catch (error) {
+        assert.include(error.toString(), "TimelockActive");
+      }
+
+      const changeId = await queueChange({
+        poolNftBoost: { poolId: POOL_ID, collection: anchor.web3.PublicKey.default, boostBps: 0 },
+      });
+      await new Promise(resolve => setTimeout(resolve, (DELAY + 1) * 1000));
+      await program.methods
+        .executeParameterChange(changeId)
+        .accountsPartial({ proposer: authority, stakingPool: stakingPoolAddress, emissionSchedule: null })
+        .rpc();
+
+      const pool = await program.account.stakingPool.fetch(stakingPoolAddress);
+      assert.equal(pool.nftBoostBps, 0);
+      assert.isTrue(pool.nftCollection.equals(anchor.web3.PublicKey.default));
+    });
+
+    it("取消排队中的变更", async () => {
+      const changeId = await queueChange({ governanceFee: { feeBps: 0 } });
+
+      await program.methods
+        .cancelParameterChange(changeId)
+        .accountsPartial({ authority, proposer: authority })
+        .rpc();
+
+      const economics = await program.account.tokenEconomics.fetch(economicsAddress);
+      assert.equal(economics.governanceFeeBps, GOVERNANCE_FEE);
+    });
+
+    after(async () => {
+      // 关闭时间锁, 以便后续测试直接修改参数
+      const changeId = await queueChange({ timelockDelay: { delay: new anchor.BN(0) } });
+      await new Promise(resolve => setTimeout(resolve, (DELAY + 1) * 1000));
+      await executeChange(changeId);
+    });
+  });
+
   // 错误情况测试
   describe("🚨 错误处理测试", () => {
     it("尝试质押低于最小数量", async () => {
@@ -413,9 +2445,10 @@ describe("token-economics", () => {
 
       try {
         await program.methods
-          .stakeTokens(POOL_ID, tooSmallAmount)
+          .stakeTokens(POOL_ID, tooSmallAmount, new anchor.BN(0))
           .accounts({
             user: authority,
+            tokenProgram: TOKEN_PROGRAM_ID,
           })
           .rpc();
         
@@ -439,9 +2472,10 @@ describe("token-economics", () => {
 
       try {
         await program.methods
-          .stakeTokens(POOL_ID, new anchor.BN(1000 * 10**9))
+          .stakeTokens(POOL_ID, new anchor.BN(1000 * 10**9), new anchor.BN(0))
           .accounts({
             user: authority,
+            tokenProgram: TOKEN_PROGRAM_ID,
           })
           .rpc();
         